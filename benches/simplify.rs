@@ -0,0 +1,121 @@
+// Compares the gcd based Fraction::simplify with the old prime trial division
+// on small, coprime and highly composite inputs. Run with `cargo bench`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use math::fraction::Fraction;
+use math::number_theory::{binary_gcd, gcd};
+
+// The trial division is_prime, next_prime and Fraction::simplify from
+// before the gcd rewrite, copied verbatim so the comparison runs the old
+// algorithm. The crate's own primes are now Miller-Rabin and sieve based.
+pub fn next_prime(p: i32) -> i32 {
+    if p < 2 {
+        return 2;
+    }
+    let mut x = if p % 2 == 0 { p + 1 } else { p + 2 };
+    loop {
+        if is_prime(x) {
+            break;
+        }
+        x += 2;
+    }
+    x
+}
+
+pub fn is_prime(x: i32) -> bool {
+    if x % 2 == 0 && x > 2 {
+        return false;
+    }
+    if x == 1 {
+        return false;
+    }
+
+    for i in (3..x).step_by(2) {
+        if x % i == 0 {
+            return false;
+        }
+    }
+
+    true
+}
+
+struct OldFraction {
+    n: i32,
+    d: i32,
+}
+
+// Kept as written, clippy's suggestions included.
+#[allow(clippy::assign_op_pattern)]
+impl OldFraction {
+    pub fn simplify(&mut self) {
+        let mut p = 2;
+        loop {
+            if (is_prime(self.n) && is_prime(self.d))
+                || (self.n == 1 || self.d == 1) 
+                || (p > self.n || p > self.d) {
+                break;
+            }
+
+            if self.n % p == 0 && self.d % p == 0 {
+                self.n = self.n / p;
+                self.d = self.d / p;
+            } else {
+                p = next_prime(p);
+            }
+        }
+    }
+}
+
+fn bench<F: FnMut()>(name: &str, mut f: F) -> Duration {
+    let mut iters = 0u32;
+    let start = Instant::now();
+    while iters < 3 || start.elapsed() < Duration::from_millis(500) {
+        f();
+        iters += 1;
+    }
+    let per_iter = start.elapsed() / iters;
+    println!("{:<40} {:>12?}/iter ({} iters)", name, per_iter, iters);
+    per_iter
+}
+
+fn main() {
+    // Small, coprime composites (2 * 49999 and 3 * 33331) and highly
+    // composite, all in the i32 the old code worked with.
+    let inputs = [(10, 15), (360, 84), (99_998, 99_993), (720_720, 1_081_080), (1 << 20, 3 << 18), (2_095_133_040, 1_396_755_360)];
+
+    for &(n, d) in &inputs {
+        let old = bench(&format!("prime trial simplify {}/{}", n, d), || {
+            let mut f = OldFraction {n: black_box(n), d: black_box(d)};
+            f.simplify();
+            black_box((f.n, f.d));
+        });
+        let new = bench(&format!("gcd simplify {}/{}", n, d), || {
+            let mut f = Fraction {n: black_box(n), d: black_box(d)};
+            f.simplify();
+            black_box(f);
+        });
+        println!("speedup: {:.1}x\n", old.as_secs_f64() / new.as_secs_f64());
+    }
+
+    // Large coprime inputs, which the old algorithm can't finish in
+    // reasonable time.
+    for &(n, d) in &[(4_000_006i64, 4_000_005i64), (999_999_999_989 * 2, 999_999_999_961 * 3)] {
+        bench(&format!("gcd simplify {}/{}", n, d), || {
+            let mut f = Fraction {n: black_box(n), d: black_box(d)};
+            f.simplify();
+            black_box(f);
+        });
+    }
+    println!();
+
+    // Consecutive Fibonacci numbers are the worst case for Euclid.
+    let (a, b) = (12_200_160_415_121_876_738u64, 7_540_113_804_746_346_429u64);
+    bench("euclid gcd fib(93), fib(92)", || {
+        black_box(gcd(black_box(a), black_box(b)));
+    });
+    bench("binary gcd fib(93), fib(92)", || {
+        black_box(binary_gcd(black_box(a), black_box(b)));
+    });
+}
@@ -0,0 +1,251 @@
+use crate::error::FractionError;
+use crate::fraction::{checked_lcm, from_u64, Fraction};
+use crate::integer::Integer;
+
+// How the integer parts are chosen once the exact shares are known.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    // Hamilton: everyone gets the floor of their share, the units left over
+    // go to the largest remainders. Good for splitting money into cents.
+    LargestRemainder,
+    // Highest averages with divisors 1, 2, 3, ... Favors larger weights.
+    DHondt,
+    // Highest averages with divisors 1, 3, 5, ...
+    SainteLague,
+}
+
+#[derive(Debug, Clone)]
+pub struct Allocation<T> {
+    // total * weight / sum of weights, reduced.
+    pub exact: Vec<Fraction<T>>,
+    // Integer parts, adding up to total.
+    pub rounded: Vec<T>,
+}
+
+fn checked<T>(v: Option<T>) -> Result<T, FractionError> {
+    v.ok_or(FractionError::Overflow)
+}
+
+// Weights as integers with the same ratios, clearing denominators with their
+// lcm (1/2 : 1/3 : 1/6 becomes 3 : 2 : 1).
+fn integer_weights<T: Integer>(weights: &[Fraction<T>]) -> Result<Vec<T>, FractionError> {
+    let mut weights = weights.to_vec();
+    for w in weights.iter_mut() {
+        if w.d.is_zero() {
+            return Err(FractionError::ZeroDenominator);
+        }
+        w.simplify();
+        if w.n.is_negative() {
+            return Err(FractionError::NotRepresentable);
+        }
+    }
+    let mut denominators: Vec<T> = weights.iter().map(|w| w.d.clone()).collect();
+    let l = checked_lcm(&mut denominators)?;
+    weights.into_iter().map(|w| checked((l.clone() / w.d).checked_mul(&w.n))).collect()
+}
+
+// Splits total into parts proportional to the weights. Ties go to the earlier
+// weight. Fails for a negative total or weight, and when there are no weights
+// or all of them are zero.
+pub fn allocate<T: Integer>(total: T, weights: &[Fraction<T>], method: Method) -> Result<Allocation<T>, FractionError> {
+    if total.is_negative() {
+        return Err(FractionError::NotRepresentable);
+    }
+    let weights = integer_weights(weights)?;
+    let mut sum = T::zero();
+    for w in &weights {
+        sum = checked(sum.checked_add(w))?;
+    }
+    if sum.is_zero() {
+        return Err(FractionError::NotRepresentable);
+    }
+
+    let mut exact = Vec::with_capacity(weights.len());
+    for w in &weights {
+        let mut share = Fraction {n: checked(total.checked_mul(w))?, d: sum.clone()};
+        share.simplify();
+        exact.push(share);
+    }
+
+    let rounded = match method {
+        Method::LargestRemainder => largest_remainder(&total, &exact),
+        Method::DHondt => highest_averages(&total, &weights, &exact, &T::one()),
+        Method::SainteLague => highest_averages(&total, &weights, &exact, &T::from_u8(2)),
+    };
+    Ok(Allocation {exact, rounded})
+}
+
+fn largest_remainder<T: Integer>(total: &T, exact: &[Fraction<T>]) -> Vec<T> {
+    let mut rounded: Vec<T> = exact.iter().map(|f| f.n.clone() / f.d.clone()).collect();
+    let remainders: Vec<Fraction<T>> = exact.iter().map(|f| Fraction {n: f.n.clone() % f.d.clone(), d: f.d.clone()}).collect();
+    let mut left = total.clone();
+    for r in &rounded {
+        left = left - r.clone();
+    }
+
+    // Stable, so equal remainders keep their order.
+    let mut order: Vec<usize> = (0..exact.len()).collect();
+    order.sort_by(|&a, &b| remainders[b].cmp(&remainders[a]));
+    for i in order {
+        if left.is_zero() {
+            break;
+        }
+        rounded[i] = rounded[i].clone() + T::one();
+        left = left - T::one();
+    }
+    rounded
+}
+
+// Hands out one unit at a time to the largest weight / (step * given + 1).
+// Going from zero would take total rounds, so every weight starts from a
+// count it is sure to reach: D'Hondt never gives less than the floor of the
+// exact share, Sainte-Laguë at most n / 2 + 1 less (n weights).
+fn highest_averages<T: Integer>(total: &T, weights: &[T], exact: &[Fraction<T>], step: &T) -> Vec<T> {
+    // None when n / 2 + 1 doesn't even fit in T, then there's no head start.
+    let slack = if step.is_one() { Some(T::zero()) } else { from_u64::<T>(weights.len() as u64 / 2 + 1).ok() };
+    let mut rounded: Vec<T> = exact
+        .iter()
+        .map(|f| {
+            let floor = f.n.clone() / f.d.clone();
+            match &slack {
+                Some(slack) if floor > *slack => floor - slack.clone(),
+                _ => T::zero(),
+            }
+        })
+        .collect();
+    let mut given = T::zero();
+    for r in &rounded {
+        given = given + r.clone();
+    }
+    while given < *total {
+        let average = |i: usize| Fraction {n: weights[i].clone(), d: step.clone() * rounded[i].clone() + T::one()};
+        let mut best = 0;
+        for i in 1..weights.len() {
+            if average(i) > average(best) {
+                best = i;
+            }
+        }
+        rounded[best] = rounded[best].clone() + T::one();
+        given = given + T::one();
+    }
+    rounded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bigint::BigInt;
+
+    fn weights(l: &[i32]) -> Vec<Fraction> {
+        l.iter().map(|&w| Fraction::from(w)).collect()
+    }
+
+    #[test]
+    fn largest_remainder_test1() {
+        let a = allocate(100, &weights(&[3, 5, 7]), Method::LargestRemainder).unwrap();
+        assert_eq!(a.exact, vec![Fraction::from(20), Fraction {n: 100, d: 3}, Fraction {n: 140, d: 3}]);
+        assert_eq!((a.exact[1].n, a.exact[1].d), (100, 3));
+        assert_eq!(a.rounded, vec![20, 33, 47]);
+    }
+
+    #[test]
+    fn penny_test1() {
+        let a = allocate(100, &weights(&[1, 1, 1]), Method::LargestRemainder).unwrap();
+        assert_eq!(a.rounded, vec![34, 33, 33]);
+        let a = allocate(1001, &weights(&[1, 0, 1]), Method::LargestRemainder).unwrap();
+        assert_eq!(a.rounded, vec![501, 0, 500]);
+        let a = allocate(0, &weights(&[2, 3]), Method::LargestRemainder).unwrap();
+        assert_eq!(a.rounded, vec![0, 0]);
+    }
+
+    #[test]
+    fn fractional_weights_test1() {
+        let w = vec![Fraction {n: 1, d: 2}, Fraction {n: 2, d: 6}, Fraction {n: 1, d: 6}];
+        let a = allocate(12, &w, Method::LargestRemainder).unwrap();
+        assert_eq!(a.rounded, vec![6, 4, 2]);
+        assert_eq!(a.exact, weights(&[6, 4, 2]));
+    }
+
+    #[test]
+    fn dhondt_test1() {
+        let votes = weights(&[100000, 80000, 30000, 20000]);
+        assert_eq!(allocate(8, &votes, Method::DHondt).unwrap().rounded, vec![4, 3, 1, 0]);
+        assert_eq!(allocate(0, &votes, Method::DHondt).unwrap().rounded, vec![0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn sainte_lague_test1() {
+        let votes = weights(&[100000, 80000, 30000, 20000]);
+        assert_eq!(allocate(8, &votes, Method::SainteLague).unwrap().rounded, vec![3, 3, 1, 1]);
+        assert_eq!(allocate(1, &weights(&[5, 5]), Method::SainteLague).unwrap().rounded, vec![1, 0]);
+    }
+
+    #[test]
+    fn sums_test1() {
+        let w = weights(&[13, 7, 29, 1, 50]);
+        for method in [Method::LargestRemainder, Method::DHondt, Method::SainteLague] {
+            for total in 0..60 {
+                let a = allocate(total, &w, method).unwrap();
+                assert_eq!(a.rounded.iter().sum::<i32>(), total);
+                assert_eq!(a.exact.iter().fold(Fraction::from(0), |acc, x| acc + x), Fraction::from(total));
+            }
+        }
+    }
+
+    #[test]
+    fn error_test1() {
+        assert_eq!(allocate(10, &[], Method::DHondt).err(), Some(FractionError::NotRepresentable));
+        assert_eq!(allocate(10, &weights(&[0, 0]), Method::DHondt).err(), Some(FractionError::NotRepresentable));
+        assert_eq!(allocate(10, &weights(&[1, -1]), Method::DHondt).err(), Some(FractionError::NotRepresentable));
+        assert_eq!(allocate(-1, &weights(&[1]), Method::DHondt).err(), Some(FractionError::NotRepresentable));
+        assert_eq!(allocate(10, &[Fraction {n: 1, d: 0}], Method::DHondt).err(), Some(FractionError::ZeroDenominator));
+        assert_eq!(allocate(i32::MAX, &weights(&[2, 3]), Method::DHondt).err(), Some(FractionError::Overflow));
+    }
+
+    #[test]
+    fn bigint_test1() {
+        let w: Vec<Fraction<BigInt>> = [1, 2, 4].iter().map(|&w| Fraction::from(BigInt::from(w))).collect();
+        let a = allocate(BigInt::from(10).pow(30), &w, Method::LargestRemainder).unwrap();
+        let sum = a.rounded.iter().fold(BigInt::from(0), |acc, x| acc + x.clone());
+        assert_eq!(sum, BigInt::from(10).pow(30));
+        assert_eq!(a.rounded[0], "142857142857142857142857142857".parse().unwrap());
+    }
+
+    // Seat by seat from zero, what highest_averages does without a head start.
+    fn naive(total: i32, votes: &[i32], step: i32) -> Vec<i32> {
+        let mut seats = vec![0; votes.len()];
+        for _ in 0..total {
+            let average = |i: usize| Fraction {n: votes[i], d: step * seats[i] + 1};
+            let best = (1..votes.len()).fold(0, |b, i| if average(i) > average(b) { i } else { b });
+            seats[best] += 1;
+        }
+        seats
+    }
+
+    #[test]
+    fn head_start_test1() {
+        for votes in [vec![13, 7, 29, 1, 50], vec![5, 5, 5], vec![1000, 1, 1, 1, 1, 1], vec![3, 0, 2]] {
+            let w: Vec<Fraction> = votes.iter().map(|&v| Fraction::from(v)).collect();
+            for total in 0..120 {
+                assert_eq!(allocate(total, &w, Method::DHondt).unwrap().rounded, naive(total, &votes, 1));
+                assert_eq!(allocate(total, &w, Method::SainteLague).unwrap().rounded, naive(total, &votes, 2));
+            }
+        }
+    }
+
+    #[test]
+    fn large_total_test1() {
+        let w: Vec<Fraction<BigInt>> = [1, 2, 4].iter().map(|&w| Fraction::from(BigInt::from(w))).collect();
+        let total = BigInt::from(10).pow(30) + BigInt::from(1);
+        let third: BigInt = "142857142857142857142857142857".parse().unwrap();
+        let a = allocate(total.clone(), &w, Method::DHondt).unwrap();
+        // 4/7 and 8/7 over the floors tie for the last seat, the earlier one wins.
+        assert_eq!(a.rounded, vec![third.clone(), &third * &BigInt::from(2) + BigInt::from(1), &third * &BigInt::from(4) + BigInt::from(1)]);
+        let a = allocate(total, &w, Method::SainteLague).unwrap();
+        assert_eq!(a.rounded, vec![third.clone(), &third * &BigInt::from(2) + BigInt::from(1), &third * &BigInt::from(4) + BigInt::from(1)]);
+
+        let w: Vec<Fraction<u64>> = [3, 5, 7, 11].iter().map(|&w| Fraction::from(w)).collect();
+        let a = allocate(u64::MAX / 16, &w, Method::SainteLague).unwrap();
+        assert_eq!(a.rounded.iter().sum::<u64>(), u64::MAX / 16);
+    }
+}
@@ -0,0 +1,218 @@
+use crate::bigint::BigInt;
+use crate::error::FractionError;
+use crate::fraction::Fraction;
+use crate::integer::Integer;
+
+// The search runs on the exact value of the double with BigInt, so deep
+// expansions don't pick up float error. Only the answer has to fit in T.
+fn to_fraction<T: Integer>(n: &BigInt, d: &BigInt) -> Result<Fraction<T>, FractionError> {
+    let n = n.to_integer().ok_or(FractionError::Overflow)?;
+    let d = d.to_integer().ok_or(FractionError::Overflow)?;
+    Ok(Fraction {n, d})
+}
+
+// |n1/d1 - x| compared with |n2/d2 - x|, all denominators positive.
+fn closer(n1: &BigInt, d1: &BigInt, n2: &BigInt, d2: &BigInt, x: &Fraction<BigInt>) -> bool {
+    let e1 = (n1 * &x.d - &x.n * d1).abs() * (d2 * &x.d);
+    let e2 = (n2 * &x.d - &x.n * d2).abs() * (d1 * &x.d);
+    e1 < e2
+}
+
+// Simplest fraction (smallest denominator) in [lo, hi], 0 <= lo <= hi,
+// walking down the Stern-Brocot tree one continued fraction term at a time.
+fn simplest(lo: (BigInt, BigInt), hi: (BigInt, BigInt)) -> (BigInt, BigInt) {
+    let (mut ln, mut ld) = lo;
+    let (mut hn, mut hd) = hi;
+    let (mut p0, mut q0, mut p1, mut q1) = (BigInt::zero(), BigInt::one(), BigInt::one(), BigInt::zero());
+    let t = loop {
+        let (a, r) = ln.divrem(&ld);
+        if r.is_zero() {
+            break a;
+        }
+        if a < &hn / &hd {
+            break a + BigInt::one();
+        }
+        // Both ends share the integer part a, go on with 1 / (x - a), which
+        // swaps the ends.
+        (ln, ld, hn, hd) = (hd.clone(), &hn - &a * &hd, ld.clone(), r);
+        (p0, q0, p1, q1) = (p1.clone(), q1.clone(), &a * &p1 + p0, &a * &q1 + q0);
+    };
+    (&t * &p1 + p0, &t * &q1 + q0)
+}
+
+// The largest value of T, found by doubling. Never call it with BigInt.
+fn largest<T: Integer>() -> BigInt {
+    let mut max = BigInt::one();
+    loop {
+        let next = &max + &max + BigInt::one();
+        if next.to_integer::<T>().is_none() {
+            return max;
+        }
+        max = next;
+    }
+}
+
+// The fraction of T closest to x, for saturating arithmetic whose exact result
+// doesn't fit. Values past the range of T clamp to T::MAX / 1 or T::MIN / 1,
+// the rest get the best approximation with both parts in range. On a tie the
+// convergent wins, like in approximate.
+pub(crate) fn nearest<T: Integer>(x: &Fraction<BigInt>) -> Fraction<T> {
+    let mut x = x.clone();
+    x.simplify();
+    let max = largest::<T>();
+    let min = if T::zero().checked_sub(&T::one()).is_some() { -&max - BigInt::one() } else { BigInt::zero() };
+    let clamp = if x.n >= &max * &x.d {
+        Some(max.clone())
+    } else if x.n <= &min * &x.d {
+        Some(min.clone())
+    } else {
+        None
+    };
+    let negative = x.n.is_negative();
+    let bound = if negative { -min } else { max.clone() };
+    let (n, d) = match clamp {
+        Some(v) => (v, BigInt::one()),
+        None if x.n.abs() <= bound && x.d <= max => (x.n, x.d),
+        None => {
+            // As in approximate, but the numerator is bounded too. |x| < bound
+            // so a0 / 1 always fits and the loop runs at least once.
+            let (mut n, mut d) = (x.n.abs(), x.d.clone());
+            let (mut p0, mut q0, mut p1, mut q1) = (BigInt::zero(), BigInt::one(), BigInt::one(), BigInt::zero());
+            loop {
+                let (a, r) = n.divrem(&d);
+                let (p2, q2) = (&p0 + &a * &p1, &q0 + &a * &q1);
+                if p2 > bound || q2 > max {
+                    break;
+                }
+                (p0, q0, p1, q1) = (p1, q1, p2, q2);
+                (n, d) = (d, r);
+            }
+            let kq = (&max - &q0) / &q1;
+            let k = if p1.is_zero() { kq } else { kq.min((&bound - &p0) / &p1) };
+            let (sn, sd) = (&p0 + &k * &p1, &q0 + &k * &q1);
+
+            let abs_x = Fraction {n: x.n.abs(), d: x.d};
+            let (n, d) = if closer(&sn, &sd, &p1, &q1, &abs_x) { (sn, sd) } else { (p1, q1) };
+            (if negative { -n } else { n }, d)
+        }
+    };
+    match to_fraction(&n, &d) {
+        Ok(f) => f,
+        Err(e) => unreachable!("{}", e),
+    }
+}
+
+impl<T: Integer> Fraction<T> {
+    // The fraction closest to x whose denominator is at most max_denominator,
+    // so approximate(3.141592653589793, 1000) is 355/113. On a tie the
+    // convergent, which has the smaller denominator, wins.
+    pub fn approximate(x: f64, max_denominator: T) -> Result<Fraction<T>, FractionError> {
+        if max_denominator < T::one() {
+            return Err(FractionError::ZeroDenominator);
+        }
+        let x = Fraction::<BigInt>::from_f64(x)?;
+        let max = BigInt::from_integer(&max_denominator);
+        if x.d <= max {
+            return to_fraction(&x.n, &x.d);
+        }
+
+        // Convergents p0/q0 and p1/q1, then the best semiconvergent between
+        // them. Floor division needs n >= 0, the sign is put back at the end.
+        let negative = x.n.is_negative();
+        let (mut n, mut d) = (x.n.abs(), x.d.clone());
+        let (mut p0, mut q0, mut p1, mut q1) = (BigInt::zero(), BigInt::one(), BigInt::one(), BigInt::zero());
+        loop {
+            let (a, r) = n.divrem(&d);
+            let q2 = &q0 + &a * &q1;
+            if q2 > max {
+                break;
+            }
+            (p0, q0, p1, q1) = (p1.clone(), q1, &p0 + &a * &p1, q2);
+            (n, d) = (d, r);
+        }
+        let k = (&max - &q0) / &q1;
+        let (sn, sd) = (&p0 + &k * &p1, &q0 + &k * &q1);
+
+        let abs_x = Fraction {n: x.n.abs(), d: x.d};
+        let (n, d) = if closer(&sn, &sd, &p1, &q1, &abs_x) { (sn, sd) } else { (p1, q1) };
+        to_fraction(&if negative { -n } else { n }, &d)
+    }
+
+    // The fraction with the smallest denominator within tolerance of x, so
+    // approximate_within(0.3333333, 1e-6) is 1/3.
+    pub fn approximate_within(x: f64, tolerance: f64) -> Result<Fraction<T>, FractionError> {
+        if tolerance.is_nan() || tolerance < 0.0 {
+            return Err(FractionError::NotRepresentable);
+        }
+        let x = Fraction::<BigInt>::from_f64(x)?;
+        let tol = Fraction::<BigInt>::from_f64(tolerance)?;
+        let lo = &x - &tol;
+        let hi = &x + &tol;
+
+        if lo.n <= BigInt::zero() && !hi.n.is_negative() {
+            return Ok(Fraction {n: T::zero(), d: T::one()});
+        }
+        let (n, d) = if hi.n.is_negative() {
+            let (n, d) = simplest((-hi.n, hi.d), (-lo.n, lo.d));
+            (-n, d)
+        } else {
+            simplest((lo.n, lo.d), (hi.n, hi.d))
+        };
+        to_fraction(&n, &d)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parts<T: Clone>(f: &Fraction<T>) -> (T, T) {
+        (f.n.clone(), f.d.clone())
+    }
+
+    #[test]
+    fn approximate_test1() {
+        let pi = std::f64::consts::PI;
+        assert_eq!(parts(&Fraction::approximate(pi, 10).unwrap()), (22, 7));
+        assert_eq!(parts(&Fraction::approximate(pi, 100).unwrap()), (311, 99));
+        assert_eq!(parts(&Fraction::approximate(pi, 1000).unwrap()), (355, 113));
+        assert_eq!(parts(&Fraction::approximate(-pi, 1000).unwrap()), (-355, 113));
+        assert_eq!(parts(&Fraction::approximate(0.3333333, 100).unwrap()), (1, 3));
+        assert_eq!(parts(&Fraction::approximate(0.7, 1).unwrap()), (1, 1));
+        assert_eq!(parts(&Fraction::approximate(0.25, 100).unwrap()), (1, 4));
+        assert_eq!(parts(&Fraction::approximate(2.0, 1).unwrap()), (2, 1));
+    }
+
+    #[test]
+    fn approximate_best_test1() {
+        // Checked against every denominator up to the limit.
+        for x in [0.1, std::f64::consts::FRAC_1_SQRT_2, 1.618033988749895, -std::f64::consts::E, 0.001] {
+            let f = Fraction::<i64>::approximate(x, 50).unwrap();
+            let err = (f.n as f64 / f.d as f64 - x).abs();
+            for d in 1..=50i64 {
+                let n = (x * d as f64).round();
+                assert!(err <= (n / d as f64 - x).abs() + 1e-15);
+            }
+        }
+    }
+
+    #[test]
+    fn approximate_error_test1() {
+        assert_eq!(Fraction::<i32>::approximate(0.5, 0), Err(FractionError::ZeroDenominator));
+        assert_eq!(Fraction::<i32>::approximate(f64::NAN, 10), Err(FractionError::NotRepresentable));
+        assert_eq!(Fraction::<i32>::approximate(1e10, 10), Err(FractionError::Overflow));
+        assert_eq!(Fraction::<u8>::approximate(-0.5, 10), Err(FractionError::Overflow));
+    }
+
+    #[test]
+    fn approximate_within_test1() {
+        assert_eq!(parts(&Fraction::approximate_within(0.3333333, 1e-6).unwrap()), (1, 3));
+        assert_eq!(parts(&Fraction::approximate_within(0.3333333, 1e-9).unwrap()), (3236246, 9708739));
+        assert_eq!(parts(&Fraction::approximate_within(std::f64::consts::PI, 1e-3).unwrap()), (201, 64));
+        assert_eq!(parts(&Fraction::approximate_within(-1.5, 0.0).unwrap()), (-3, 2));
+        assert_eq!(parts(&Fraction::approximate_within(0.01, 0.05).unwrap()), (0, 1));
+        assert_eq!(parts(&Fraction::approximate_within(2.9, 0.2).unwrap()), (3, 1));
+        assert_eq!(parts(&Fraction::approximate_within(-0.66, 0.01).unwrap()), (-2, 3));
+        assert_eq!(Fraction::<i32>::approximate_within(0.5, -1.0), Err(FractionError::NotRepresentable));
+    }
+}
@@ -0,0 +1,653 @@
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+use std::str::FromStr;
+
+use crate::integer::Integer;
+
+// Arbitrary-precision signed integer. The magnitude is stored as base 2^32
+// limbs, least significant first, without trailing zero limbs (zero is an
+// empty vector and is never negative).
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+pub struct BigInt {
+    negative: bool,
+    mag: Vec<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseBigIntError {
+    pub position: usize,
+}
+
+impl fmt::Display for ParseBigIntError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid digit at position {}", self.position)
+    }
+}
+
+impl Error for ParseBigIntError {}
+
+impl BigInt {
+    fn from_parts(negative: bool, mut mag: Vec<u32>) -> BigInt {
+        while mag.last() == Some(&0) {
+            mag.pop();
+        }
+        let negative = negative && !mag.is_empty();
+        BigInt { negative, mag }
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn abs(&self) -> BigInt {
+        BigInt { negative: false, mag: self.mag.clone() }
+    }
+
+    // Quotient truncated toward zero and remainder with the sign of self,
+    // the same convention used by the primitive integer types.
+    pub fn divrem(&self, other: &BigInt) -> (BigInt, BigInt) {
+        if other.mag.is_empty() {
+            panic!("attempt to divide by zero");
+        }
+        let (q, r) = divrem_mag(&self.mag, &other.mag);
+        (
+            BigInt::from_parts(self.negative != other.negative, q),
+            BigInt::from_parts(self.negative, r),
+        )
+    }
+
+    pub fn pow(&self, mut exp: u32) -> BigInt {
+        let mut base = self.clone();
+        let mut result = BigInt::one();
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = &result * &base;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = &base * &base;
+            }
+        }
+        result
+    }
+
+    pub fn bits(&self) -> u64 {
+        match self.mag.last() {
+            None => 0,
+            Some(top) => (self.mag.len() as u64 - 1) * 32 + (32 - top.leading_zeros()) as u64,
+        }
+    }
+
+    // Conversions from and to any Integer, one hex digit at a time so only
+    // from_u8 and checked arithmetic are needed on the other side.
+    pub(crate) fn from_integer<T: Integer>(v: &T) -> BigInt {
+        let sixteen = T::from_u8(16);
+        let negative = v.is_negative();
+        let mut v = v.clone();
+        let mut nibbles = Vec::new();
+        while !v.is_zero() {
+            nibbles.push((v.clone() % sixteen.clone()).to_f64().abs() as u32);
+            v = v / sixteen.clone();
+        }
+        let mut mag = vec![0u32; nibbles.len().div_ceil(8)];
+        for (i, nibble) in nibbles.iter().enumerate() {
+            mag[i / 8] |= nibble << (4 * (i % 8));
+        }
+        BigInt::from_parts(negative, mag)
+    }
+
+    pub(crate) fn to_integer<T: Integer>(&self) -> Option<T> {
+        let sixteen = T::from_u8(16);
+        let mut r = T::zero();
+        for limb in self.mag.iter().rev() {
+            for shift in (0..8).rev() {
+                let nibble = T::from_u8((limb >> (4 * shift) & 0xf) as u8);
+                r = r.checked_mul(&sixteen)?;
+                r = if self.negative { r.checked_sub(&nibble)? } else { r.checked_add(&nibble)? };
+            }
+        }
+        Some(r)
+    }
+}
+
+fn cmp_mag(a: &[u32], b: &[u32]) -> Ordering {
+    if a.len() != b.len() {
+        return a.len().cmp(&b.len());
+    }
+    for i in (0..a.len()).rev() {
+        if a[i] != b[i] {
+            return a[i].cmp(&b[i]);
+        }
+    }
+    Ordering::Equal
+}
+
+fn add_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut r = Vec::with_capacity(long.len() + 1);
+    let mut carry = 0u64;
+    for (i, &x) in long.iter().enumerate() {
+        let s = x as u64 + *short.get(i).unwrap_or(&0) as u64 + carry;
+        r.push(s as u32);
+        carry = s >> 32;
+    }
+    if carry > 0 {
+        r.push(carry as u32);
+    }
+    r
+}
+
+// a - b, requires |a| >= |b|.
+fn sub_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut r = Vec::with_capacity(a.len());
+    let mut borrow = 0i64;
+    for (i, &x) in a.iter().enumerate() {
+        let mut t = x as i64 - *b.get(i).unwrap_or(&0) as i64 - borrow;
+        borrow = 0;
+        if t < 0 {
+            t += 1 << 32;
+            borrow = 1;
+        }
+        r.push(t as u32);
+    }
+    r
+}
+
+fn mul_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let mut r = vec![0u32; a.len() + b.len()];
+    for (i, &x) in a.iter().enumerate() {
+        let mut carry = 0u64;
+        for (j, &y) in b.iter().enumerate() {
+            let t = x as u64 * y as u64 + r[i + j] as u64 + carry;
+            r[i + j] = t as u32;
+            carry = t >> 32;
+        }
+        r[i + b.len()] = carry as u32;
+    }
+    r
+}
+
+fn divrem_small(a: &[u32], v: u32) -> (Vec<u32>, u32) {
+    let mut q = vec![0u32; a.len()];
+    let mut rem = 0u64;
+    for i in (0..a.len()).rev() {
+        let cur = (rem << 32) | a[i] as u64;
+        q[i] = (cur / v as u64) as u32;
+        rem = cur % v as u64;
+    }
+    (q, rem as u32)
+}
+
+// Knuth, TAOCP vol. 2, 4.3.1, algorithm D.
+fn divrem_mag(a: &[u32], b: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    if cmp_mag(a, b) == Ordering::Less {
+        return (Vec::new(), a.to_vec());
+    }
+    if b.len() == 1 {
+        let (q, r) = divrem_small(a, b[0]);
+        return (q, vec![r]);
+    }
+
+    let s = b[b.len() - 1].leading_zeros();
+    let v = shl_bits(b, s);
+    let mut u = shl_bits(a, s);
+    u.push(0);
+    let n = b.len();
+    let m = a.len() - n;
+    let mut q = vec![0u32; m + 1];
+
+    for j in (0..=m).rev() {
+        let num = ((u[j + n] as u64) << 32) | u[j + n - 1] as u64;
+        let mut qhat = num / v[n - 1] as u64;
+        let mut rhat = num % v[n - 1] as u64;
+        while qhat >= 1 << 32 || qhat * v[n - 2] as u64 > ((rhat << 32) | u[j + n - 2] as u64) {
+            qhat -= 1;
+            rhat += v[n - 1] as u64;
+            if rhat >= 1 << 32 {
+                break;
+            }
+        }
+
+        let mut borrow = 0i64;
+        let mut carry = 0u64;
+        for i in 0..n {
+            let p = qhat * v[i] as u64 + carry;
+            carry = p >> 32;
+            let t = u[i + j] as i64 - borrow - (p & 0xffff_ffff) as i64;
+            u[i + j] = t as u32;
+            borrow = if t < 0 { 1 } else { 0 };
+        }
+        let t = u[j + n] as i64 - borrow - carry as i64;
+        u[j + n] = t as u32;
+
+        if t < 0 {
+            qhat -= 1;
+            let mut c = 0u64;
+            for i in 0..n {
+                let s = u[i + j] as u64 + v[i] as u64 + c;
+                u[i + j] = s as u32;
+                c = s >> 32;
+            }
+            u[j + n] = u[j + n].wrapping_add(c as u32);
+        }
+        q[j] = qhat as u32;
+    }
+
+    (q, shr_bits(&u[..n], s))
+}
+
+// Shifts left by s < 32 bits, keeping the same number of limbs plus the carry
+// only when it is not zero.
+fn shl_bits(a: &[u32], s: u32) -> Vec<u32> {
+    if s == 0 {
+        return a.to_vec();
+    }
+    let mut r = Vec::with_capacity(a.len() + 1);
+    let mut carry = 0u32;
+    for &x in a {
+        r.push((x << s) | carry);
+        carry = x >> (32 - s);
+    }
+    if carry > 0 {
+        r.push(carry);
+    }
+    r
+}
+
+fn shr_bits(a: &[u32], s: u32) -> Vec<u32> {
+    if s == 0 {
+        return a.to_vec();
+    }
+    let mut r = vec![0u32; a.len()];
+    for i in 0..a.len() {
+        r[i] = a[i] >> s;
+        if i + 1 < a.len() {
+            r[i] |= a[i + 1] << (32 - s);
+        }
+    }
+    r
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &BigInt) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => cmp_mag(&self.mag, &other.mag),
+            (true, true) => cmp_mag(&other.mag, &self.mag),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &BigInt) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<'a> Add<&'a BigInt> for &'a BigInt {
+    type Output = BigInt;
+
+    fn add(self, other: &BigInt) -> BigInt {
+        if self.negative == other.negative {
+            return BigInt::from_parts(self.negative, add_mag(&self.mag, &other.mag));
+        }
+        match cmp_mag(&self.mag, &other.mag) {
+            Ordering::Less => BigInt::from_parts(other.negative, sub_mag(&other.mag, &self.mag)),
+            _ => BigInt::from_parts(self.negative, sub_mag(&self.mag, &other.mag)),
+        }
+    }
+}
+
+impl<'a> Sub<&'a BigInt> for &'a BigInt {
+    type Output = BigInt;
+
+    fn sub(self, other: &BigInt) -> BigInt {
+        self + &(-other)
+    }
+}
+
+impl<'a> Mul<&'a BigInt> for &'a BigInt {
+    type Output = BigInt;
+
+    fn mul(self, other: &BigInt) -> BigInt {
+        BigInt::from_parts(self.negative != other.negative, mul_mag(&self.mag, &other.mag))
+    }
+}
+
+impl<'a> Div<&'a BigInt> for &'a BigInt {
+    type Output = BigInt;
+
+    fn div(self, other: &BigInt) -> BigInt {
+        self.divrem(other).0
+    }
+}
+
+impl<'a> Rem<&'a BigInt> for &'a BigInt {
+    type Output = BigInt;
+
+    fn rem(self, other: &BigInt) -> BigInt {
+        self.divrem(other).1
+    }
+}
+
+macro_rules! forward_owned_ops {
+    ($($tr:ident $f:ident),*) => {
+        $(
+            impl $tr<BigInt> for BigInt {
+                type Output = BigInt;
+
+                fn $f(self, other: BigInt) -> BigInt {
+                    (&self).$f(&other)
+                }
+            }
+
+            impl<'a> $tr<&'a BigInt> for BigInt {
+                type Output = BigInt;
+
+                fn $f(self, other: &'a BigInt) -> BigInt {
+                    (&self).$f(other)
+                }
+            }
+
+            impl<'a> $tr<BigInt> for &'a BigInt {
+                type Output = BigInt;
+
+                fn $f(self, other: BigInt) -> BigInt {
+                    self.$f(&other)
+                }
+            }
+        )*
+    };
+}
+
+forward_owned_ops!(Add add, Sub sub, Mul mul, Div div, Rem rem);
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::from_parts(!self.negative, self.mag.clone())
+    }
+}
+
+impl Neg for BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::from_parts(!self.negative, self.mag)
+    }
+}
+
+macro_rules! impl_from_unsigned {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for BigInt {
+                fn from(v: $t) -> BigInt {
+                    let mut v = v as u128;
+                    let mut mag = Vec::new();
+                    while v > 0 {
+                        mag.push(v as u32);
+                        v >>= 32;
+                    }
+                    BigInt { negative: false, mag }
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_from_signed {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for BigInt {
+                fn from(v: $t) -> BigInt {
+                    let b = BigInt::from(v.unsigned_abs());
+                    BigInt::from_parts(v < 0, b.mag)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_unsigned!(u8, u16, u32, u64, u128, usize);
+impl_from_signed!(i8, i16, i32, i64, i128, isize);
+
+impl Integer for BigInt {
+    fn zero() -> Self {
+        BigInt::default()
+    }
+
+    fn one() -> Self {
+        BigInt::from(1u8)
+    }
+
+    fn from_u8(v: u8) -> Self {
+        BigInt::from(v)
+    }
+
+    fn to_f64(&self) -> f64 {
+        let mut r = 0.0;
+        for &limb in self.mag.iter().rev() {
+            r = r * 4_294_967_296.0 + limb as f64;
+        }
+        if self.negative { -r } else { r }
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        self.to_integer()
+    }
+
+    fn from_u64(v: u64) -> Option<Self> {
+        Some(BigInt::from(v))
+    }
+
+    fn is_zero(&self) -> bool {
+        self.mag.is_empty()
+    }
+
+    // A BigInt never overflows, only a division by zero can fail.
+    fn checked_add(&self, v: &Self) -> Option<Self> {
+        Some(self + v)
+    }
+
+    fn checked_sub(&self, v: &Self) -> Option<Self> {
+        Some(self - v)
+    }
+
+    fn checked_mul(&self, v: &Self) -> Option<Self> {
+        Some(self * v)
+    }
+
+    fn checked_div(&self, v: &Self) -> Option<Self> {
+        if v.is_zero() { None } else { Some(self / v) }
+    }
+
+    fn saturating_add(&self, v: &Self) -> Self {
+        self + v
+    }
+
+    fn saturating_sub(&self, v: &Self) -> Self {
+        self - v
+    }
+
+    fn saturating_mul(&self, v: &Self) -> Self {
+        self * v
+    }
+
+    fn saturating_div(&self, v: &Self) -> Self {
+        self / v
+    }
+
+    fn wrapping_add(&self, v: &Self) -> Self {
+        self + v
+    }
+
+    fn wrapping_sub(&self, v: &Self) -> Self {
+        self - v
+    }
+
+    fn wrapping_mul(&self, v: &Self) -> Self {
+        self * v
+    }
+
+    fn wrapping_div(&self, v: &Self) -> Self {
+        self / v
+    }
+
+    fn checked_neg(&self) -> Option<Self> {
+        Some(-self)
+    }
+
+    fn is_negative(&self) -> bool {
+        self.negative
+    }
+}
+
+impl FromStr for BigInt {
+    type Err = ParseBigIntError;
+
+    fn from_str(s: &str) -> Result<BigInt, ParseBigIntError> {
+        let (negative, digits, offset) = match s.as_bytes().first() {
+            Some(b'-') => (true, &s[1..], 1),
+            Some(b'+') => (false, &s[1..], 1),
+            _ => (false, s, 0),
+        };
+        if digits.is_empty() {
+            return Err(ParseBigIntError { position: offset });
+        }
+
+        let mut mag: Vec<u32> = Vec::new();
+        for (i, c) in digits.bytes().enumerate() {
+            if !c.is_ascii_digit() {
+                return Err(ParseBigIntError { position: offset + i });
+            }
+            let mut carry = (c - b'0') as u64;
+            for limb in mag.iter_mut() {
+                let t = *limb as u64 * 10 + carry;
+                *limb = t as u32;
+                carry = t >> 32;
+            }
+            if carry > 0 {
+                mag.push(carry as u32);
+            }
+        }
+        Ok(BigInt::from_parts(negative, mag))
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.mag.is_empty() {
+            return f.pad_integral(true, "", "0");
+        }
+        let mut chunks = Vec::new();
+        let mut mag = self.mag.clone();
+        while !mag.is_empty() {
+            let (q, r) = divrem_small(&mag, 1_000_000_000);
+            chunks.push(r);
+            mag = q;
+            while mag.last() == Some(&0) {
+                mag.pop();
+            }
+        }
+        let mut s = chunks.pop().unwrap().to_string();
+        for c in chunks.iter().rev() {
+            s.push_str(&format!("{:09}", c));
+        }
+        f.pad_integral(!self.negative, "", &s)
+    }
+}
+
+impl fmt::Debug for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn big(s: &str) -> BigInt {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn parse_display_test1() {
+        for s in ["0", "7", "-7", "4294967296", "-18446744073709551616", "123456789012345678901234567890"] {
+            assert_eq!(big(s).to_string(), s);
+        }
+        assert_eq!(big("+0042").to_string(), "42");
+        assert_eq!(big("-0").to_string(), "0");
+    }
+
+    #[test]
+    fn parse_error_test1() {
+        assert_eq!("12a4".parse::<BigInt>(), Err(ParseBigIntError { position: 2 }));
+        assert_eq!("-".parse::<BigInt>(), Err(ParseBigIntError { position: 1 }));
+        assert_eq!("".parse::<BigInt>(), Err(ParseBigIntError { position: 0 }));
+    }
+
+    #[test]
+    fn add_sub_test1() {
+        assert_eq!(big("4294967295") + big("1"), big("4294967296"));
+        assert_eq!(big("4294967296") - big("1"), big("4294967295"));
+        assert_eq!(big("5") - big("12"), big("-7"));
+        assert_eq!(big("-5") + big("12"), big("7"));
+        assert_eq!(big("-5") - big("-5"), BigInt::zero());
+    }
+
+    #[test]
+    fn mul_test1() {
+        assert_eq!(big("-123456789012345678901234567890") * big("987654321098765432109876543210"),
+                   big("-121932631137021795226185032733622923332237463801111263526900"));
+        assert_eq!(big("2").pow(128), BigInt::from(u128::MAX) + BigInt::one());
+    }
+
+    #[test]
+    fn divrem_test1() {
+        let a = big("121932631137021795226185032733622923332237463801111263526907");
+        let b = big("987654321098765432109876543210");
+        let (q, r) = a.divrem(&b);
+        assert_eq!(q, big("123456789012345678901234567890"));
+        assert_eq!(r, big("7"));
+    }
+
+    #[test]
+    fn divrem_test2() {
+        assert_eq!(big("-7") / big("2"), big("-3"));
+        assert_eq!(big("-7") % big("2"), big("-1"));
+        assert_eq!(big("7") % big("-2"), big("1"));
+        assert_eq!(big("340282366920938463463374607431768211456") / big("18446744073709551617"), big("18446744073709551615"));
+        assert_eq!(big("340282366920938463463374607431768211456") % big("18446744073709551617"), big("1"));
+    }
+
+    #[test]
+    fn cmp_test1() {
+        assert!(big("-10") < big("-9"));
+        assert!(big("-1") < big("0"));
+        assert!(big("18446744073709551616") > big("18446744073709551615"));
+    }
+
+    #[test]
+    fn to_f64_test1() {
+        assert_eq!(big("-18446744073709551616").to_f64(), -18446744073709551616.0);
+    }
+
+    #[test]
+    fn integer_conversion_test1() {
+        assert_eq!(BigInt::from_integer(&i64::MIN), BigInt::from(i64::MIN));
+        assert_eq!(BigInt::from_integer(&u128::MAX), BigInt::from(u128::MAX));
+        assert_eq!(BigInt::from_integer(&0u8), BigInt::zero());
+        assert_eq!(BigInt::from(i32::MIN).to_integer::<i32>(), Some(i32::MIN));
+        assert_eq!(BigInt::from(-1).to_integer::<u8>(), None);
+        assert_eq!(BigInt::from(256).to_integer::<u8>(), None);
+        assert_eq!(big("-123456789012").to_integer::<i64>(), Some(-123456789012));
+    }
+}
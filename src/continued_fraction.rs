@@ -0,0 +1,372 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::error::FractionError;
+use crate::fraction::{div_floor, Fraction};
+use crate::integer::Integer;
+
+// Terms [a0; a1, a2, ...] of n/d. a0 is the floor and can be negative, the
+// others are positive and the last one is greater than 1 (unless it is a0).
+// Every form of the same value, reduced or not, gives the same terms.
+pub fn expand<T: Integer>(f: &Fraction<T>) -> Vec<T> {
+    assert!(!f.d.is_zero(), "attempt to divide by zero");
+    let (mut n, mut d) = (f.n.clone(), f.d.clone());
+    let mut terms = Vec::new();
+    while !d.is_zero() {
+        // The remainder has the sign of d, so from the second term on n/d > 1.
+        let (a, r) = div_floor(&n, &d);
+        terms.push(a);
+        (n, d) = (d, r);
+    }
+    terms
+}
+
+// The value of [a0; a1, ..., ak], reduced.
+pub fn collapse<T: Integer>(terms: &[T]) -> Result<Fraction<T>, FractionError> {
+    if terms.is_empty() {
+        return Err(FractionError::NotRepresentable);
+    }
+    let mut it = convergents(terms.iter().cloned());
+    let mut f = it.by_ref().last().ok_or(FractionError::Overflow)?;
+    if it.overflowed {
+        return Err(FractionError::Overflow);
+    }
+    if f.d.is_zero() {
+        return Err(FractionError::ZeroDenominator);
+    }
+    // Only needed when a term after a0 isn't positive.
+    f.simplify();
+    Ok(f)
+}
+
+// a * p1 + p0
+fn next_term<T: Integer>(a: &T, p1: &T, p0: &T) -> Option<T> {
+    a.checked_mul(p1)?.checked_add(p0)
+}
+
+// The fractions p_k / q_k cut after each term. They come out reduced and
+// alternate around the value, each one closer than the one before. The
+// iterator stops if the next one doesn't fit in T.
+pub struct Convergents<T, I> {
+    terms: I,
+    p0: T,
+    q0: T,
+    p1: T,
+    q1: T,
+    overflowed: bool,
+}
+
+pub fn convergents<T: Integer, I: IntoIterator<Item = T>>(terms: I) -> Convergents<T, I::IntoIter> {
+    Convergents {
+        terms: terms.into_iter(),
+        p0: T::zero(),
+        q0: T::one(),
+        p1: T::one(),
+        q1: T::zero(),
+        overflowed: false,
+    }
+}
+
+impl<T: Integer, I: Iterator<Item = T>> Iterator for Convergents<T, I> {
+    type Item = Fraction<T>;
+
+    fn next(&mut self) -> Option<Fraction<T>> {
+        if self.overflowed {
+            return None;
+        }
+        let a = self.terms.next()?;
+        match (next_term(&a, &self.p1, &self.p0), next_term(&a, &self.q1, &self.q0)) {
+            (Some(p), Some(q)) => {
+                self.p0 = std::mem::replace(&mut self.p1, p.clone());
+                self.q0 = std::mem::replace(&mut self.q1, q.clone());
+                Some(Fraction {n: p, d: q})
+            }
+            _ => {
+                self.overflowed = true;
+                None
+            }
+        }
+    }
+}
+
+// The convergents with the intermediate fractions between them:
+// (p_{k-2} + j * p_{k-1}) / (q_{k-2} + j * q_{k-1}) for j = 1..=a_k, where
+// j = a_k is the convergent itself. For pi that is 3, 4, 7/2, ..., 22/7,
+// 25/8, ... Stops like Convergents on overflow.
+pub struct Semiconvergents<T, I> {
+    terms: I,
+    p0: T,
+    q0: T,
+    p1: T,
+    q1: T,
+    a: T,
+    j: T,
+    overflowed: bool,
+}
+
+pub fn semiconvergents<T: Integer, I: IntoIterator<Item = T>>(terms: I) -> Semiconvergents<T, I::IntoIter> {
+    Semiconvergents {
+        terms: terms.into_iter(),
+        p0: T::zero(),
+        q0: T::one(),
+        p1: T::one(),
+        q1: T::zero(),
+        a: T::zero(),
+        j: T::zero(),
+        overflowed: false,
+    }
+}
+
+impl<T: Integer, I: Iterator<Item = T>> Iterator for Semiconvergents<T, I> {
+    type Item = Fraction<T>;
+
+    fn next(&mut self) -> Option<Fraction<T>> {
+        if self.overflowed {
+            return None;
+        }
+        // a0 is taken whole, the later terms one step at a time.
+        if self.j >= self.a {
+            let first = self.q1.is_zero();
+            self.a = self.terms.next()?;
+            self.j = if first { self.a.clone() } else { T::one() };
+        } else {
+            self.j = self.j.clone() + T::one();
+        }
+
+        let (Some(p), Some(q)) = (next_term(&self.j, &self.p1, &self.p0), next_term(&self.j, &self.q1, &self.q0)) else {
+            self.overflowed = true;
+            return None;
+        };
+        if self.j == self.a {
+            self.p0 = std::mem::replace(&mut self.p1, p.clone());
+            self.q0 = std::mem::replace(&mut self.q1, q.clone());
+        }
+        Some(Fraction {n: p, d: q})
+    }
+}
+
+impl<T: Integer> Fraction<T> {
+    pub fn continued_fraction(&self) -> Vec<T> {
+        expand(self)
+    }
+
+    pub fn from_continued_fraction(terms: &[T]) -> Result<Fraction<T>, FractionError> {
+        collapse(terms)
+    }
+
+    pub fn convergents(&self) -> Convergents<T, std::vec::IntoIter<T>> {
+        convergents(expand(self))
+    }
+
+    pub fn semiconvergents(&self) -> Semiconvergents<T, std::vec::IntoIter<T>> {
+        semiconvergents(expand(self))
+    }
+}
+
+// Expansion of a quadratic irrational: the head terms, then the period
+// repeated forever. Rational values have an empty period.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Periodic {
+    pub head: Vec<i64>,
+    pub period: Vec<i64>,
+}
+
+impl Periodic {
+    pub fn terms(&self) -> impl Iterator<Item = i64> + '_ {
+        self.head.iter().chain(self.period.iter().cycle()).copied()
+    }
+
+    pub fn convergents(&self) -> Convergents<i64, impl Iterator<Item = i64> + '_> {
+        convergents(self.terms())
+    }
+}
+
+// [4; 1, 3, 1, 8, 1, 3, 1, 8, ...] is written [4; (1, 3, 1, 8)].
+impl fmt::Display for Periodic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[")?;
+        for (i, a) in self.head.iter().enumerate() {
+            match i {
+                0 => write!(f, "{}", a)?,
+                1 => write!(f, "; {}", a)?,
+                _ => write!(f, ", {}", a)?,
+            }
+        }
+        if !self.period.is_empty() {
+            let sep = match self.head.len() {
+                0 => "",
+                1 => "; ",
+                _ => ", ",
+            };
+            let period: Vec<String> = self.period.iter().map(|a| a.to_string()).collect();
+            write!(f, "{}({})", sep, period.join(", "))?;
+        }
+        write!(f, "]")
+    }
+}
+
+// (p + sqrt(d)) / q. Every step keeps the value in that form with
+// q | d - p^2, and as there are finitely many such (p, q) pairs the
+// terms repeat once a pair is seen again. The work is done in i128, which
+// large p and q can still overflow (p^2 and q^2 once scaled), and the terms
+// have to fit in an i64.
+pub fn quadratic(p: i64, d: u64, q: i64) -> Result<Periodic, FractionError> {
+    if q == 0 {
+        return Err(FractionError::ZeroDenominator);
+    }
+    let overflow = |x: Option<i128>| x.ok_or(FractionError::Overflow);
+    let term = |a: i128| i64::try_from(a).map_err(|_| FractionError::Overflow);
+    let s = d.isqrt();
+    if s * s == d {
+        let f = Fraction {n: p as i128 + s as i128, d: q as i128};
+        let head = expand(&f).into_iter().map(term).collect::<Result<_, _>>()?;
+        return Ok(Periodic {head, period: Vec::new()});
+    }
+
+    let (mut p, mut d, mut q) = (p as i128, d as i128, q as i128);
+    if (d - p * p) % q != 0 {
+        // Scale by |q| so the divisibility holds.
+        p = overflow(p.checked_mul(q.abs()))?;
+        d = overflow(d.checked_mul(q * q))?;
+        q = overflow(q.checked_mul(q.abs()))?;
+    }
+    let s = (d as u128).isqrt() as i128;
+
+    let mut seen = HashMap::new();
+    let mut terms = Vec::new();
+    let start = loop {
+        if let Some(&start) = seen.get(&(p, q)) {
+            break start;
+        }
+        seen.insert((p, q), terms.len());
+        // floor((p + sqrt(d)) / q) without the square root: p + s and
+        // p + s + 1 bound the numerator and no multiple of q lies between.
+        let top = overflow(p.checked_add(s))?;
+        let a = if q > 0 { div_floor(&top, &q).0 } else { div_floor(&overflow(top.checked_add(1))?, &q).0 };
+        terms.push(term(a)?);
+        p = overflow(overflow(a.checked_mul(q))?.checked_sub(p))?;
+        q = overflow(d.checked_sub(overflow(p.checked_mul(p))?))? / q;
+    };
+    let period = terms.split_off(start);
+    Ok(Periodic {head: terms, period})
+}
+
+pub fn sqrt(n: u64) -> Periodic {
+    match quadratic(0, n, 1) {
+        Ok(x) => x,
+        // p stays below sqrt(n) and q below 2 sqrt(n), far from the limits.
+        Err(e) => unreachable!("{}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bigint::BigInt;
+
+    fn fr(n: i32, d: i32) -> Fraction {
+        Fraction {n, d}
+    }
+
+    fn parts(l: impl Iterator<Item = Fraction<i64>>) -> Vec<(i64, i64)> {
+        l.map(|f| (f.n, f.d)).collect()
+    }
+
+    #[test]
+    fn expand_test1() {
+        assert_eq!(expand(&fr(415, 93)), vec![4, 2, 6, 7]);
+        assert_eq!(expand(&fr(830, 186)), vec![4, 2, 6, 7]);
+        assert_eq!(expand(&fr(-415, 93)), vec![-5, 1, 1, 6, 7]);
+        assert_eq!(expand(&fr(415, -93)), vec![-5, 1, 1, 6, 7]);
+        assert_eq!(expand(&fr(3, 1)), vec![3]);
+        assert_eq!(expand(&fr(0, 5)), vec![0]);
+        assert_eq!(expand(&fr(1, 2)), vec![0, 2]);
+    }
+
+    #[test]
+    fn collapse_test1() {
+        assert_eq!(collapse(&[4, 2, 6, 7]).map(|f| (f.n, f.d)), Ok((415, 93)));
+        assert_eq!(collapse(&[-5, 1, 1, 6, 7]).map(|f| (f.n, f.d)), Ok((-415, 93)));
+        assert_eq!(collapse(&[0, 2]).map(|f| (f.n, f.d)), Ok((1, 2)));
+        assert_eq!(collapse(&[1, 2, 0, 2]).map(|f| (f.n, f.d)), Ok((5, 4)));
+        assert_eq!(collapse::<i32>(&[]), Err(FractionError::NotRepresentable));
+        assert_eq!(collapse(&[1, 0]), Err(FractionError::ZeroDenominator));
+        assert_eq!(collapse(&[1i8, 100, 100]), Err(FractionError::Overflow));
+        for f in [fr(13, 12), fr(-7, 3), fr(1, 1000), fr(i32::MAX, 2)] {
+            assert_eq!(Fraction::from_continued_fraction(&f.continued_fraction()), Ok(f));
+        }
+    }
+
+    #[test]
+    fn convergents_test1() {
+        let pi = [3i64, 7, 15, 1, 292, 1];
+        assert_eq!(parts(convergents(pi)), vec![(3, 1), (22, 7), (333, 106), (355, 113), (103993, 33102), (104348, 33215)]);
+        let f = Fraction {n: 415i64, d: 93};
+        assert_eq!(parts(f.convergents()), vec![(4, 1), (9, 2), (58, 13), (415, 93)]);
+        assert_eq!(convergents([100i8, 100, 100]).count(), 1);
+    }
+
+    #[test]
+    fn semiconvergents_test1() {
+        let f = Fraction {n: 415i64, d: 93};
+        assert_eq!(
+            parts(f.semiconvergents()),
+            vec![(4, 1), (5, 1), (9, 2), (13, 3), (22, 5), (31, 7), (40, 9), (49, 11), (58, 13),
+                 (67, 15), (125, 28), (183, 41), (241, 54), (299, 67), (357, 80), (415, 93)]
+        );
+        let sc = parts(semiconvergents([3i64, 7, 15]));
+        assert_eq!(&sc[..3], &[(3, 1), (4, 1), (7, 2)]);
+        assert_eq!(sc[7], (22, 7));
+        assert_eq!(sc.last(), Some(&(333, 106)));
+        assert_eq!(sc.len(), 1 + 7 + 15);
+    }
+
+    #[test]
+    fn sqrt_test1() {
+        assert_eq!(sqrt(2), Periodic {head: vec![1], period: vec![2]});
+        assert_eq!(sqrt(7), Periodic {head: vec![2], period: vec![1, 1, 1, 4]});
+        assert_eq!(sqrt(23).to_string(), "[4; (1, 3, 1, 8)]");
+        assert_eq!(sqrt(16).to_string(), "[4]");
+        assert_eq!(sqrt(0).to_string(), "[0]");
+        assert_eq!(sqrt(u64::MAX).period.last(), Some(&(2 * (u32::MAX as i64))));
+    }
+
+    #[test]
+    fn quadratic_test1() {
+        // Golden ratio.
+        assert_eq!(quadratic(1, 5, 2).unwrap().to_string(), "[(1)]");
+        assert_eq!(quadratic(-1, 5, 2).unwrap().to_string(), "[0; (1)]");
+        assert_eq!(quadratic(0, 2, -1).unwrap().to_string(), "[-2; 1, 1, (2)]");
+        assert_eq!(quadratic(3, 9, 4).unwrap().to_string(), "[1; 2]");
+        assert_eq!(quadratic(1, 3, 3).unwrap().to_string(), "[0; 1, (10, 5)]");
+    }
+
+    #[test]
+    fn quadratic_error_test1() {
+        assert_eq!(quadratic(1, 2, 0), Err(FractionError::ZeroDenominator));
+        // Scaling by |q| pushes d q^2 past i128.
+        assert_eq!(quadratic(i64::MAX, 3, i64::MAX - 1), Err(FractionError::Overflow));
+        assert_eq!(quadratic(1, u64::MAX, i64::MIN), Err(FractionError::Overflow));
+        // The first term alone is bigger than an i64.
+        assert_eq!(quadratic(i64::MAX, 4, 1), Err(FractionError::Overflow));
+        assert_eq!(quadratic(i64::MAX, 2, 1), Err(FractionError::Overflow));
+        assert_eq!(quadratic(i64::MAX - 10, 2, 1).unwrap().period, vec![2]);
+    }
+
+    #[test]
+    fn pell_test1() {
+        // The convergents of sqrt(2) solve x^2 - 2y^2 = +-1.
+        for f in sqrt(2).convergents().take(20) {
+            assert_eq!((f.n * f.n - 2 * f.d * f.d).abs(), 1);
+        }
+        let (x, y) = sqrt(61).convergents().map(|f| (f.n, f.d)).find(|&(x, y)| x * x - 61 * y * y == 1).unwrap();
+        assert_eq!((x, y), (1766319049, 226153980));
+    }
+
+    #[test]
+    fn bigint_test1() {
+        let f = Fraction {n: BigInt::from(2).pow(100) + BigInt::from(1), d: BigInt::from(3).pow(40)};
+        let terms = f.continued_fraction();
+        assert_eq!(Fraction::from_continued_fraction(&terms), Ok(f));
+    }
+}
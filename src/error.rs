@@ -0,0 +1,26 @@
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FractionError {
+    ZeroDenominator,
+    Overflow,
+    // Position (in bytes) of the offending character in the parsed input.
+    ParseError { position: usize },
+    // The value has no exact representation in the requested type (NaN,
+    // infinity, a negative value for an unsigned type...).
+    NotRepresentable,
+}
+
+impl fmt::Display for FractionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FractionError::ZeroDenominator => write!(f, "denominator is zero"),
+            FractionError::Overflow => write!(f, "arithmetic overflow"),
+            FractionError::ParseError { position } => write!(f, "invalid character at position {}", position),
+            FractionError::NotRepresentable => write!(f, "value is not representable"),
+        }
+    }
+}
+
+impl Error for FractionError {}
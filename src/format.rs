@@ -0,0 +1,421 @@
+use std::fmt;
+
+use crate::error::FractionError;
+use crate::fraction::{to_big, Fraction, FractionMixed};
+use crate::integer::Integer;
+
+// How a fraction is written. Display picks Plain, Mixed with {:#} and
+// Decimal with {:.N}; the other styles are reached through Fraction::format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FractionStyle {
+    // 13/12
+    Plain,
+    // 1 1/12
+    Mixed,
+    // ¹³⁄₁₂, or the vulgar fraction character when there is one, like ½
+    Unicode,
+    // \frac{13}{12}
+    Latex,
+    // 1.08, with this many digits after the point, rounded half to even
+    Decimal(usize),
+    // 1.08(3), exact with the repeating digits in parentheses
+    Repeating,
+}
+
+pub struct FractionFormat<T> {
+    fraction: Fraction<T>,
+    style: FractionStyle,
+}
+
+impl<T: Integer> Fraction<T> {
+    pub fn format(&self, style: FractionStyle) -> FractionFormat<T> {
+        FractionFormat { fraction: self.clone(), style }
+    }
+}
+
+impl<T: Integer> FractionMixed<T> {
+    pub fn format(&self, style: FractionStyle) -> FractionFormat<T> {
+        FractionFormat { fraction: self.get_fraction(), style }
+    }
+}
+
+const SUPERSCRIPT: [char; 10] = ['⁰', '¹', '²', '³', '⁴', '⁵', '⁶', '⁷', '⁸', '⁹'];
+const SUBSCRIPT: [char; 10] = ['₀', '₁', '₂', '₃', '₄', '₅', '₆', '₇', '₈', '₉'];
+
+const VULGAR: [(&str, &str, char); 18] = [
+    ("1", "2", '½'), ("1", "3", '⅓'), ("2", "3", '⅔'), ("1", "4", '¼'), ("3", "4", '¾'),
+    ("1", "5", '⅕'), ("2", "5", '⅖'), ("3", "5", '⅗'), ("4", "5", '⅘'), ("1", "6", '⅙'),
+    ("5", "6", '⅚'), ("1", "7", '⅐'), ("1", "8", '⅛'), ("3", "8", '⅜'), ("5", "8", '⅝'),
+    ("7", "8", '⅞'), ("1", "9", '⅑'), ("1", "10", '⅒'),
+];
+
+fn map_digits(s: &str, table: &[char; 10]) -> String {
+    s.bytes().map(|c| table[(c - b'0') as usize]).collect()
+}
+
+// A negative T::MIN has no magnitude in T, so fractions with one are
+// written through BigInt instead.
+fn has_min<T: Integer>(fr: &Fraction<T>) -> bool {
+    [&fr.n, &fr.d].into_iter().any(|x| x.is_negative() && x.checked_neg().is_none())
+}
+
+// 10 * rem / d and 10 * rem % d for 0 <= rem < d, adding rem ten times so no
+// intermediate value reaches d.
+fn next_digit<T: Integer>(rem: T, d: &T) -> (u8, T) {
+    let mut acc = T::zero();
+    let mut digit = 0;
+    let gap = d.clone() - rem.clone();
+    for _ in 0..10 {
+        if acc >= gap {
+            acc = acc - gap.clone();
+            digit += 1;
+        } else {
+            acc = acc + rem.clone();
+        }
+    }
+    (digit, acc)
+}
+
+// n / d with `precision` digits, n >= 0 and d > 0.
+fn decimal<T: Integer>(n: &T, d: &T, precision: usize) -> String {
+    let mut whole = n.clone() / d.clone();
+    let mut rem = n.clone() % d.clone();
+    let mut digits = Vec::with_capacity(precision);
+    for _ in 0..precision {
+        let (digit, r) = next_digit(rem, d);
+        digits.push(digit);
+        rem = r;
+    }
+
+    let half = d.clone() - rem.clone();
+    let last_odd = match digits.last() {
+        Some(digit) => digit % 2 == 1,
+        None => !(whole.clone() % T::from_u8(2)).is_zero(),
+    };
+    if rem > half || (rem == half && last_odd) {
+        let mut carry = true;
+        for digit in digits.iter_mut().rev() {
+            if *digit == 9 {
+                *digit = 0;
+            } else {
+                *digit += 1;
+                carry = false;
+                break;
+            }
+        }
+        if carry {
+            whole = whole + T::one();
+        }
+    }
+
+    let mut s = whole.to_string();
+    if precision > 0 {
+        s.push('.');
+        s.extend(digits.iter().map(|digit| (b'0' + digit) as char));
+    }
+    s
+}
+
+// n / d written out exactly: whole.digits followed by the repetend, the block
+// of digits that repeats forever (empty when the expansion terminates).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecimalExpansion<T> {
+    pub negative: bool,
+    pub whole: T,
+    pub digits: Vec<u8>,
+    pub repetend: Vec<u8>,
+}
+
+impl<T: Integer> Fraction<T> {
+    // 1/7 is 0.(142857) and 1/6 is 0.1(6). The digits before the repetend
+    // come from the powers of 2 and 5 in the reduced denominator, and the
+    // repetend can be as long as the denominator minus one.
+    pub fn to_decimal_expansion(&self) -> DecimalExpansion<T> {
+        match self.checked_to_decimal_expansion() {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    // Only fails on a zero denominator, or with Overflow when the whole part
+    // is |T::MIN| (T::MIN / 1).
+    pub fn checked_to_decimal_expansion(&self) -> Result<DecimalExpansion<T>, FractionError> {
+        if self.d.is_zero() {
+            return Err(FractionError::ZeroDenominator);
+        }
+        if has_min(self) {
+            let e = to_big(self).checked_to_decimal_expansion()?;
+            let whole = e.whole.to_integer().ok_or(FractionError::Overflow)?;
+            return Ok(DecimalExpansion {negative: e.negative, whole, digits: e.digits, repetend: e.repetend});
+        }
+        let mut f = self.clone();
+        f.simplify();
+        let negative = f.n.is_negative();
+        let n = if negative { T::zero() - f.n } else { f.n };
+        let d = f.d;
+
+        let mut fixed = 0;
+        for p in [2, 5] {
+            let p = T::from_u8(p);
+            let (mut k, mut r) = (0, d.clone());
+            while (r.clone() % p.clone()).is_zero() {
+                r = r / p.clone();
+                k += 1;
+            }
+            fixed = fixed.max(k);
+        }
+
+        let whole = n.clone() / d.clone();
+        let mut rem = n % d.clone();
+        let mut digits = Vec::with_capacity(fixed);
+        for _ in 0..fixed {
+            let (digit, r) = next_digit(rem, &d);
+            digits.push(digit);
+            rem = r;
+        }
+        let mut repetend = Vec::new();
+        if !rem.is_zero() {
+            let start = rem.clone();
+            loop {
+                let (digit, r) = next_digit(rem, &d);
+                repetend.push(digit);
+                rem = r;
+                if rem == start {
+                    break;
+                }
+            }
+        }
+        Ok(DecimalExpansion {negative, whole, digits, repetend})
+    }
+}
+
+impl<T: Integer> fmt::Display for DecimalExpansion<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut body = self.whole.to_string();
+        if !self.digits.is_empty() || !self.repetend.is_empty() {
+            body.push('.');
+            body.extend(self.digits.iter().map(|digit| (b'0' + digit) as char));
+        }
+        if !self.repetend.is_empty() {
+            body.push('(');
+            body.extend(self.repetend.iter().map(|digit| (b'0' + digit) as char));
+            body.push(')');
+        }
+        f.pad_integral(!self.negative, "", &body)
+    }
+}
+
+impl<T: Integer> fmt::Display for FractionFormat<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if has_min(&self.fraction) {
+            return fmt::Display::fmt(&to_big(&self.fraction).format(self.style), f);
+        }
+        let mut fr = self.fraction.clone();
+        if fr.d.is_negative() {
+            fr = Fraction {n: T::zero() - fr.n, d: T::zero() - fr.d};
+        }
+        let negative = fr.n.is_negative();
+        let n = if negative { T::zero() - fr.n } else { fr.n };
+        let d = fr.d;
+
+        let body = match self.style {
+            _ if d.is_zero() => format!("{}/{}", n, d),
+            FractionStyle::Decimal(precision) => decimal(&n, &d, precision),
+            FractionStyle::Repeating => Fraction {n, d}.to_decimal_expansion().to_string(),
+            _ if d.is_one() => n.to_string(),
+            FractionStyle::Plain => format!("{}/{}", n, d),
+            FractionStyle::Mixed => {
+                let whole = n.clone() / d.clone();
+                let rem = n % d.clone();
+                if rem.is_zero() {
+                    whole.to_string()
+                } else if whole.is_zero() {
+                    format!("{}/{}", rem, d)
+                } else {
+                    format!("{} {}/{}", whole, rem, d)
+                }
+            }
+            FractionStyle::Unicode => {
+                let (n, d) = (n.to_string(), d.to_string());
+                match VULGAR.iter().find(|v| v.0 == n && v.1 == d) {
+                    Some(v) => v.2.to_string(),
+                    None => format!("{}⁄{}", map_digits(&n, &SUPERSCRIPT), map_digits(&d, &SUBSCRIPT)),
+                }
+            }
+            FractionStyle::Latex => format!("\\frac{{{}}}{{{}}}", n, d),
+        };
+        f.pad_integral(!negative, "", &body)
+    }
+}
+
+impl<T: Integer> fmt::Display for Fraction<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let style = match f.precision() {
+            Some(precision) => FractionStyle::Decimal(precision),
+            None if f.alternate() => FractionStyle::Mixed,
+            None => FractionStyle::Plain,
+        };
+        fmt::Display::fmt(&self.format(style), f)
+    }
+}
+
+impl<T: Integer> fmt::Display for FractionMixed<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let style = match f.precision() {
+            Some(precision) => FractionStyle::Decimal(precision),
+            None => FractionStyle::Mixed,
+        };
+        fmt::Display::fmt(&self.format(style), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bigint::BigInt;
+
+    fn fr(n: i32, d: i32) -> Fraction {
+        Fraction {n, d}
+    }
+
+    #[test]
+    fn plain_test1() {
+        assert_eq!(fr(13, 12).to_string(), "13/12");
+        assert_eq!(fr(10, 15).to_string(), "10/15");
+        assert_eq!(fr(-3, 4).to_string(), "-3/4");
+        assert_eq!(fr(3, -4).to_string(), "-3/4");
+        assert_eq!(fr(5, 1).to_string(), "5");
+        assert_eq!(fr(0, 7).to_string(), "0/7");
+    }
+
+    #[test]
+    fn padding_test1() {
+        assert_eq!(format!("{:>6}", fr(3, 4)), "   3/4");
+        assert_eq!(format!("{:<6}|", fr(-3, 4)), "-3/4  |");
+        assert_eq!(format!("{:+}", fr(3, 4)), "+3/4");
+        assert_eq!(format!("{:+.1}", fr(3, 4)), "+0.8");
+    }
+
+    #[test]
+    fn mixed_test1() {
+        assert_eq!(format!("{:#}", fr(13, 12)), "1 1/12");
+        assert_eq!(format!("{:#}", fr(-7, 4)), "-1 3/4");
+        assert_eq!(format!("{:#}", fr(3, 4)), "3/4");
+        assert_eq!(format!("{:#}", fr(8, 4)), "2");
+        assert_eq!(FractionMixed {num_improprio: 1, fracao: fr(1, 12)}.to_string(), "1 1/12");
+        assert_eq!(FractionMixed {num_improprio: -2, fracao: fr(1, 4)}.to_string(), "-1 3/4");
+    }
+
+    #[test]
+    fn decimal_test1() {
+        assert_eq!(format!("{:.3}", fr(1, 3)), "0.333");
+        assert_eq!(format!("{:.2}", fr(2, 3)), "0.67");
+        assert_eq!(format!("{:.4}", fr(13, 12)), "1.0833");
+        assert_eq!(format!("{:.2}", fr(999, 1000)), "1.00");
+        assert_eq!(format!("{:.0}", fr(2, 3)), "1");
+        assert_eq!(format!("{:.2}", fr(-1, 8)), "-0.12");
+        assert_eq!(format!("{:.2}", FractionMixed {num_improprio: 1, fracao: fr(1, 4)}), "1.25");
+    }
+
+    #[test]
+    fn decimal_half_even_test1() {
+        assert_eq!(format!("{:.2}", fr(1, 8)), "0.12");
+        assert_eq!(format!("{:.2}", fr(3, 8)), "0.38");
+        assert_eq!(format!("{:.0}", fr(5, 2)), "2");
+        assert_eq!(format!("{:.0}", fr(7, 2)), "4");
+        assert_eq!(format!("{:.2}", fr(1, 8)), format!("{:.2}", 0.125));
+    }
+
+    #[test]
+    fn decimal_overflow_test1() {
+        let f: Fraction<i32> = Fraction {n: i32::MAX - 1, d: i32::MAX};
+        assert_eq!(format!("{:.12}", f), "0.999999999534");
+        let g: Fraction<u8> = Fraction {n: 254, d: 255};
+        assert_eq!(format!("{:.5}", g), "0.99608");
+    }
+
+    #[test]
+    fn min_test1() {
+        assert_eq!(fr(i32::MIN, 1).to_string(), "-2147483648");
+        assert_eq!(fr(i32::MIN, 3).to_string(), "-2147483648/3");
+        assert_eq!(format!("{:#}", fr(i32::MIN, 3)), "-715827882 2/3");
+        assert_eq!(format!("{:.2}", fr(i32::MIN, 3)), "-715827882.67");
+        assert_eq!(fr(i32::MIN, -1).format(FractionStyle::Plain).to_string(), "2147483648");
+        assert_eq!(fr(1, i32::MIN).to_string(), "-1/2147483648");
+        assert_eq!(format!("{:>16}", fr(i32::MIN, 1)), "     -2147483648");
+        let f: Fraction<i8> = Fraction {n: i8::MIN, d: 7};
+        assert_eq!(f.format(FractionStyle::Latex).to_string(), "-\\frac{128}{7}");
+    }
+
+    #[test]
+    fn unicode_test1() {
+        assert_eq!(fr(13, 12).format(FractionStyle::Unicode).to_string(), "¹³⁄₁₂");
+        assert_eq!(fr(1, 2).format(FractionStyle::Unicode).to_string(), "½");
+        assert_eq!(fr(-3, 4).format(FractionStyle::Unicode).to_string(), "-¾");
+        assert_eq!(fr(2, 4).format(FractionStyle::Unicode).to_string(), "²⁄₄");
+    }
+
+    #[test]
+    fn latex_test1() {
+        assert_eq!(fr(13, 12).format(FractionStyle::Latex).to_string(), "\\frac{13}{12}");
+        assert_eq!(fr(1, -2).format(FractionStyle::Latex).to_string(), "-\\frac{1}{2}");
+        assert_eq!(fr(4, 1).format(FractionStyle::Latex).to_string(), "4");
+    }
+
+    #[test]
+    fn builder_test1() {
+        assert_eq!(fr(13, 12).format(FractionStyle::Mixed).to_string(), "1 1/12");
+        assert_eq!(fr(13, 12).format(FractionStyle::Decimal(2)).to_string(), "1.08");
+        assert_eq!(FractionMixed {num_improprio: 1, fracao: fr(1, 12)}.format(FractionStyle::Plain).to_string(), "13/12");
+    }
+
+    #[test]
+    fn decimal_expansion_test1() {
+        assert_eq!(fr(1, 7).to_decimal_expansion().to_string(), "0.(142857)");
+        assert_eq!(fr(1, 6).to_decimal_expansion().to_string(), "0.1(6)");
+        assert_eq!(fr(-7, 6).to_decimal_expansion().to_string(), "-1.1(6)");
+        assert_eq!(fr(1, 12).to_decimal_expansion().to_string(), "0.08(3)");
+        assert_eq!(fr(22, 7).to_decimal_expansion().to_string(), "3.(142857)");
+        assert_eq!(fr(3, -4).to_decimal_expansion().to_string(), "-0.75");
+        assert_eq!(fr(6, 2).to_decimal_expansion().to_string(), "3");
+        assert_eq!(fr(0, -2).to_decimal_expansion().to_string(), "0");
+        assert_eq!(
+            fr(1, 6).to_decimal_expansion(),
+            DecimalExpansion {negative: false, whole: 0, digits: vec![1], repetend: vec![6]}
+        );
+        assert_eq!(fr(1, 97).to_decimal_expansion().repetend.len(), 96);
+        assert_eq!(format!("{:>8}", fr(1, 3).format(FractionStyle::Repeating)), "   0.(3)");
+    }
+
+    #[test]
+    fn decimal_expansion_min_test1() {
+        assert_eq!(fr(i32::MIN, 3).to_decimal_expansion().to_string(), "-715827882.(6)");
+        assert_eq!(fr(i32::MIN, 3).to_decimal_expansion().whole, 715827882);
+        assert_eq!(fr(1, i32::MIN).to_decimal_expansion().to_string(), "-0.0000000004656612873077392578125");
+        assert_eq!(fr(i32::MIN, i32::MIN).to_decimal_expansion().to_string(), "1");
+        let f: Fraction<i8> = Fraction {n: i8::MIN, d: -3};
+        assert_eq!(f.to_decimal_expansion().to_string(), "42.(6)");
+        assert_eq!(fr(i32::MIN, 1).checked_to_decimal_expansion(), Err(FractionError::Overflow));
+        assert_eq!(fr(1, 0).checked_to_decimal_expansion(), Err(FractionError::ZeroDenominator));
+        assert_eq!(fr(i32::MIN, 1).format(FractionStyle::Repeating).to_string(), "-2147483648");
+    }
+
+    #[test]
+    fn decimal_expansion_round_trip_test1() {
+        for d in 1..60 {
+            for n in -70..70 {
+                let f = fr(n, d);
+                let s = f.format(FractionStyle::Repeating).to_string();
+                let g = s.parse::<Fraction<BigInt>>().unwrap();
+                assert_eq!(g, Fraction {n: BigInt::from(n), d: BigInt::from(d)}, "{}", s);
+            }
+        }
+    }
+
+    #[test]
+    fn bigint_test1() {
+        let f = Fraction {n: BigInt::from(2).pow(70), d: BigInt::from(3)};
+        assert_eq!(f.to_string(), "1180591620717411303424/3");
+        assert_eq!(format!("{:.3}", f), "393530540239137101141.333");
+    }
+}
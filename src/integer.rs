@@ -0,0 +1,142 @@
+use std::fmt::{Debug, Display};
+use std::ops::{Add, Div, Mul, Rem, Sub};
+
+// Every numeric type that can be used as numerator/denominator of a Fraction.
+pub trait Integer:
+    Clone
+    + Debug
+    + Display
+    + Ord
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+{
+    fn zero() -> Self;
+    fn one() -> Self;
+
+    // Only used for small constants like 2, 3 or 10.
+    fn from_u8(v: u8) -> Self;
+
+    fn to_f64(&self) -> f64;
+
+    // None when the value doesn't fit, like TryFrom.
+    fn to_u64(&self) -> Option<u64>;
+    fn from_u64(v: u64) -> Option<Self>;
+
+    fn checked_add(&self, v: &Self) -> Option<Self>;
+    fn checked_sub(&self, v: &Self) -> Option<Self>;
+    fn checked_mul(&self, v: &Self) -> Option<Self>;
+    fn checked_div(&self, v: &Self) -> Option<Self>;
+
+    fn saturating_add(&self, v: &Self) -> Self;
+    fn saturating_sub(&self, v: &Self) -> Self;
+    fn saturating_mul(&self, v: &Self) -> Self;
+    fn saturating_div(&self, v: &Self) -> Self;
+
+    fn wrapping_add(&self, v: &Self) -> Self;
+    fn wrapping_sub(&self, v: &Self) -> Self;
+    fn wrapping_mul(&self, v: &Self) -> Self;
+    fn wrapping_div(&self, v: &Self) -> Self;
+
+    fn checked_neg(&self) -> Option<Self>;
+
+    fn is_zero(&self) -> bool {
+        *self == Self::zero()
+    }
+
+    fn is_one(&self) -> bool {
+        *self == Self::one()
+    }
+
+    fn is_negative(&self) -> bool {
+        *self < Self::zero()
+    }
+}
+
+macro_rules! impl_integer {
+    ($($t:ty),*) => {
+        $(
+            impl Integer for $t {
+                fn zero() -> Self {
+                    0
+                }
+
+                fn one() -> Self {
+                    1
+                }
+
+                fn from_u8(v: u8) -> Self {
+                    v as $t
+                }
+
+                fn to_f64(&self) -> f64 {
+                    *self as f64
+                }
+
+                fn to_u64(&self) -> Option<u64> {
+                    u64::try_from(*self).ok()
+                }
+
+                fn from_u64(v: u64) -> Option<Self> {
+                    <$t>::try_from(v).ok()
+                }
+
+                fn checked_add(&self, v: &Self) -> Option<Self> {
+                    <$t>::checked_add(*self, *v)
+                }
+
+                fn checked_sub(&self, v: &Self) -> Option<Self> {
+                    <$t>::checked_sub(*self, *v)
+                }
+
+                fn checked_mul(&self, v: &Self) -> Option<Self> {
+                    <$t>::checked_mul(*self, *v)
+                }
+
+                fn checked_div(&self, v: &Self) -> Option<Self> {
+                    <$t>::checked_div(*self, *v)
+                }
+
+                fn saturating_add(&self, v: &Self) -> Self {
+                    <$t>::saturating_add(*self, *v)
+                }
+
+                fn saturating_sub(&self, v: &Self) -> Self {
+                    <$t>::saturating_sub(*self, *v)
+                }
+
+                fn saturating_mul(&self, v: &Self) -> Self {
+                    <$t>::saturating_mul(*self, *v)
+                }
+
+                fn saturating_div(&self, v: &Self) -> Self {
+                    <$t>::saturating_div(*self, *v)
+                }
+
+                fn wrapping_add(&self, v: &Self) -> Self {
+                    <$t>::wrapping_add(*self, *v)
+                }
+
+                fn wrapping_sub(&self, v: &Self) -> Self {
+                    <$t>::wrapping_sub(*self, *v)
+                }
+
+                fn wrapping_mul(&self, v: &Self) -> Self {
+                    <$t>::wrapping_mul(*self, *v)
+                }
+
+                fn wrapping_div(&self, v: &Self) -> Self {
+                    <$t>::wrapping_div(*self, *v)
+                }
+
+                fn checked_neg(&self) -> Option<Self> {
+                    <$t>::checked_neg(*self)
+                }
+            }
+        )*
+    };
+}
+
+impl_integer!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
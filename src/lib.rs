@@ -0,0 +1,15 @@
+pub mod allocation;
+mod approximate;
+pub mod bigint;
+pub mod continued_fraction;
+pub mod error;
+pub mod format;
+pub mod fraction;
+pub mod integer;
+pub mod modular;
+pub mod number_theory;
+mod parse;
+pub mod percentage;
+pub mod prime;
+pub mod round;
+pub mod sieve;
@@ -1,9 +1,4 @@
-use fraction::Fraction;
-use crate::{fraction::{lcm, FractionMixed}};
-
-pub mod fraction;
-pub mod percentage;
-pub mod prime;
+use math::fraction::{lcm, Fraction, FractionMixed};
 
 fn main() {
     let mut x = [5, 2];
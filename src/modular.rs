@@ -0,0 +1,409 @@
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use crate::bigint::BigInt;
+use crate::fraction::Fraction;
+use crate::integer::Integer;
+use crate::number_theory::{extended_gcd, gcd};
+
+// a * b mod m, through a u128 product so nothing overflows.
+pub fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+    (a as u128 * b as u128 % m as u128) as u64
+}
+
+// base^exp mod m by repeated squaring.
+pub fn pow_mod(mut base: u64, mut exp: u64, m: u64) -> u64 {
+    let mut r = 1 % m;
+    base %= m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            r = mul_mod(r, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exp >>= 1;
+    }
+    r
+}
+
+// Arithmetic mod an odd u128 in Montgomery form (R = 2^128), so products
+// never need more than 128 bits.
+pub(crate) struct Montgomery {
+    m: u128,
+    // -m^-1 mod R
+    inv: u128,
+    // R^2 mod m
+    r2: u128,
+}
+
+// The 256 bit product as (high, low).
+fn mul_wide(a: u128, b: u128) -> (u128, u128) {
+    const MASK: u128 = u64::MAX as u128;
+    let (a1, a0, b1, b0) = (a >> 64, a & MASK, b >> 64, b & MASK);
+    let (p00, p01, p10, p11) = (a0 * b0, a0 * b1, a1 * b0, a1 * b1);
+    let mid = (p00 >> 64) + (p01 & MASK) + (p10 & MASK);
+    ((p11 + (p01 >> 64) + (p10 >> 64) + (mid >> 64)), (p00 & MASK) | (mid << 64))
+}
+
+impl Montgomery {
+    pub(crate) fn new(m: u128) -> Montgomery {
+        debug_assert!(m & 1 == 1);
+        // Newton's iteration doubles the correct bits: 3, 6, ..., 192.
+        let mut inv = m;
+        for _ in 0..6 {
+            inv = inv.wrapping_mul(2u128.wrapping_sub(m.wrapping_mul(inv)));
+        }
+        let mut r2 = 0u128.wrapping_sub(m) % m;
+        for _ in 0..128 {
+            r2 = add_mod_u128(r2, r2, m);
+        }
+        Montgomery {m, inv: inv.wrapping_neg(), r2}
+    }
+
+    fn reduce(&self, (hi, lo): (u128, u128)) -> u128 {
+        let q = lo.wrapping_mul(self.inv);
+        let (qh, ql) = mul_wide(q, self.m);
+        // lo + ql is 0 mod R, with a carry unless both are 0.
+        let carry = (lo != 0 || ql != 0) as u128;
+        let (t, over) = hi.overflowing_add(qh + carry);
+        if over || t >= self.m { t.wrapping_sub(self.m) } else { t }
+    }
+
+    pub(crate) fn mul(&self, a: u128, b: u128) -> u128 {
+        self.reduce(mul_wide(a, b))
+    }
+
+    // a -> a * R mod m and back.
+    pub(crate) fn encode(&self, a: u128) -> u128 {
+        self.mul(a % self.m, self.r2)
+    }
+
+    pub(crate) fn decode(&self, a: u128) -> u128 {
+        self.reduce((0, a))
+    }
+
+    pub(crate) fn pow(&self, base: u128, mut exp: u128) -> u128 {
+        let (mut r, mut base) = (self.encode(1), base);
+        while exp > 0 {
+            if exp & 1 == 1 {
+                r = self.mul(r, base);
+            }
+            base = self.mul(base, base);
+            exp >>= 1;
+        }
+        r
+    }
+}
+
+pub(crate) fn add_mod_u128(a: u128, b: u128, m: u128) -> u128 {
+    let (s, over) = a.overflowing_add(b);
+    if over || s >= m { s.wrapping_sub(m) } else { s }
+}
+
+
+// a * b mod m for a u128 modulus, which has no wider type to multiply in:
+// below 2^64 it is the u64 case, above it doubles and adds.
+pub fn mul_mod_u128(a: u128, b: u128, m: u128) -> u128 {
+    if let Ok(m) = u64::try_from(m) {
+        return mul_mod((a % m as u128) as u64, (b % m as u128) as u64, m) as u128;
+    }
+    let (mut a, mut b, mut r) = (a % m, b % m, 0);
+    while b > 0 {
+        if b & 1 == 1 {
+            r = add_mod_u128(r, a, m);
+        }
+        a = add_mod_u128(a, a, m);
+        b >>= 1;
+    }
+    r
+}
+
+// base^exp mod m, in Montgomery form when m is odd.
+pub fn pow_mod_u128(base: u128, mut exp: u128, m: u128) -> u128 {
+    if let (Ok(m), Ok(exp)) = (u64::try_from(m), u64::try_from(exp)) {
+        return pow_mod((base % m as u128) as u64, exp, m) as u128;
+    }
+    if m & 1 == 1 {
+        let mont = Montgomery::new(m);
+        return mont.decode(mont.pow(mont.encode(base), exp));
+    }
+    let (mut r, mut base) = (1 % m, base % m);
+    while exp > 0 {
+        if exp & 1 == 1 {
+            r = mul_mod_u128(r, base, m);
+        }
+        base = mul_mod_u128(base, base, m);
+        exp >>= 1;
+    }
+    r
+}
+
+// The modular inverse: x in 0..m with a * x = 1 mod m, None when a and m
+// are not coprime.
+pub fn mod_inverse(a: u64, m: u64) -> Option<u64> {
+    if m == 0 {
+        return None;
+    }
+    let (g, x, _) = extended_gcd(a as i128, m as i128);
+    (g == 1).then(|| x.rem_euclid(m as i128) as u64)
+}
+
+// Chinese remainder theorem: the x in 0..M with x = a mod m for every (a, m),
+// with M the lcm of the moduli, returned as (x, M). The moduli don't have to
+// be coprime; None when the congruences contradict each other, a modulus is
+// 0 or M doesn't fit in a u64. No congruences give (0, 1).
+pub fn crt(congruences: &[(u64, u64)]) -> Option<(u64, u64)> {
+    let (mut x, mut m) = (0u64, 1u64);
+    for &(a, n) in congruences {
+        if n == 0 {
+            return None;
+        }
+        let a = a % n;
+        // x + m * k = a mod n, solvable when g divides a - x.
+        let g = gcd(m, n);
+        let diff = (a as i128 - x as i128).rem_euclid(n as i128) as u64;
+        if !diff.is_multiple_of(g) {
+            return None;
+        }
+        let n_g = n / g;
+        let k = mul_mod(diff / g, mod_inverse(m / g % n_g, n_g)?, n_g);
+        let l = m.checked_mul(n_g)?;
+        x = ((x as u128 + m as u128 * k as u128) % l as u128) as u64;
+        m = l;
+    }
+    Some((x, m))
+}
+
+// An integer mod M, kept in 0..M. Division panics when the divisor has no
+// inverse, which can only happen when M is not prime or the divisor is 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ModInt<const M: u64>(u64);
+
+impl<const M: u64> ModInt<M> {
+    pub fn new(v: u64) -> ModInt<M> {
+        const { assert!(M > 0, "modulus must be positive") };
+        ModInt(v % M)
+    }
+
+    pub fn value(self) -> u64 {
+        self.0
+    }
+
+    pub fn pow(self, exp: u64) -> ModInt<M> {
+        ModInt(pow_mod(self.0, exp, M))
+    }
+
+    pub fn inverse(self) -> Option<ModInt<M>> {
+        mod_inverse(self.0, M).map(ModInt)
+    }
+
+    // n / d mod M, None when d has no inverse mod M.
+    pub fn from_fraction<T: Integer>(f: &Fraction<T>) -> Option<ModInt<M>> {
+        let n = ModInt::from(BigInt::from_integer(&f.n));
+        let d = ModInt::from(BigInt::from_integer(&f.d));
+        Some(n * d.inverse()?)
+    }
+}
+
+impl<const M: u64> From<u64> for ModInt<M> {
+    fn from(v: u64) -> ModInt<M> {
+        ModInt::new(v)
+    }
+}
+
+impl<const M: u64> From<i64> for ModInt<M> {
+    fn from(v: i64) -> ModInt<M> {
+        ModInt::new((v as i128).rem_euclid(M as i128) as u64)
+    }
+}
+
+impl<const M: u64> From<BigInt> for ModInt<M> {
+    fn from(v: BigInt) -> ModInt<M> {
+        let m = BigInt::from(M);
+        let r = &(&(&v % &m) + &m) % &m;
+        ModInt::new(r.to_integer().unwrap())
+    }
+}
+
+impl<const M: u64> fmt::Display for ModInt<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl<const M: u64> Add for ModInt<M> {
+    type Output = ModInt<M>;
+
+    fn add(self, other: ModInt<M>) -> ModInt<M> {
+        ModInt(add_mod_u128(self.0 as u128, other.0 as u128, M as u128) as u64)
+    }
+}
+
+impl<const M: u64> Sub for ModInt<M> {
+    type Output = ModInt<M>;
+
+    fn sub(self, other: ModInt<M>) -> ModInt<M> {
+        self + -other
+    }
+}
+
+impl<const M: u64> Mul for ModInt<M> {
+    type Output = ModInt<M>;
+
+    fn mul(self, other: ModInt<M>) -> ModInt<M> {
+        ModInt(mul_mod(self.0, other.0, M))
+    }
+}
+
+impl<const M: u64> Div for ModInt<M> {
+    type Output = ModInt<M>;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, other: ModInt<M>) -> ModInt<M> {
+        match other.inverse() {
+            Some(inv) => self * inv,
+            None => panic!("{} has no inverse mod {}", other.0, M),
+        }
+    }
+}
+
+impl<const M: u64> Neg for ModInt<M> {
+    type Output = ModInt<M>;
+
+    fn neg(self) -> ModInt<M> {
+        ModInt((M - self.0) % M)
+    }
+}
+
+impl<const M: u64> AddAssign for ModInt<M> {
+    fn add_assign(&mut self, other: ModInt<M>) {
+        *self = *self + other;
+    }
+}
+
+impl<const M: u64> SubAssign for ModInt<M> {
+    fn sub_assign(&mut self, other: ModInt<M>) {
+        *self = *self - other;
+    }
+}
+
+impl<const M: u64> MulAssign for ModInt<M> {
+    fn mul_assign(&mut self, other: ModInt<M>) {
+        *self = *self * other;
+    }
+}
+
+impl<const M: u64> DivAssign for ModInt<M> {
+    fn div_assign(&mut self, other: ModInt<M>) {
+        *self = *self / other;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const P: u64 = 1_000_000_007;
+    type Mint = ModInt<P>;
+
+    #[test]
+    fn pow_mod_test1() {
+        assert_eq!(pow_mod(2, 10, 1000), 24);
+        assert_eq!(pow_mod(5, 0, 1), 0);
+        assert_eq!(pow_mod(u64::MAX, u64::MAX, u64::MAX - 1), 1);
+        assert_eq!(mul_mod(u64::MAX - 1, u64::MAX - 1, u64::MAX), 1);
+        // Fermat: a^(p-1) = 1 mod p.
+        assert_eq!(pow_mod(123456789, 18446744073709551556, 18446744073709551557), 1);
+    }
+
+    #[test]
+    fn pow_mod_u128_test1() {
+        let p = (1u128 << 127) - 1;
+        assert_eq!(pow_mod_u128(3, p - 1, p), 1);
+        assert_eq!(pow_mod_u128(3, p, p), 3);
+        assert_eq!(pow_mod_u128(2, 200, 1 << 100), 0);
+        assert_eq!(pow_mod_u128(3, 5, u128::MAX - 1), 243);
+        assert_eq!(pow_mod_u128(7, 1 << 70, 1000), pow_mod(pow_mod(7, 1 << 35, 1000), 1 << 35, 1000) as u128);
+        assert_eq!(mul_mod_u128(u128::MAX, u128::MAX, u128::MAX - 1), 1);
+        assert_eq!(mul_mod_u128(p - 1, p - 1, p), 1);
+    }
+
+    #[test]
+    fn mod_inverse_test1() {
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(10, 17), Some(12));
+        assert_eq!(mod_inverse(6, 9), None);
+        assert_eq!(mod_inverse(0, 7), None);
+        assert_eq!(mod_inverse(5, 1), Some(0));
+        assert_eq!(mod_inverse(5, 0), None);
+        let m = 18446744073709551557;
+        let x = mod_inverse(u64::MAX, m).unwrap();
+        assert_eq!(mul_mod(u64::MAX, x, m), 1);
+    }
+
+    #[test]
+    fn crt_test1() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[]), Some((0, 1)));
+        assert_eq!(crt(&[(5, 7)]), Some((5, 7)));
+        assert_eq!(crt(&[(12, 7)]), Some((5, 7)));
+        // Not coprime.
+        assert_eq!(crt(&[(2, 4), (4, 6)]), Some((10, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[(3, 10), (3, 10)]), Some((3, 10)));
+        assert_eq!(crt(&[(1, 0)]), None);
+        assert_eq!(crt(&[(1, 1 << 40), (2, 3 << 40)]), None);
+        assert_eq!(crt(&[(1, 1 << 40), (1, (1 << 40) + 1), (1, 1 << 30)]), None);
+        let (x, m) = crt(&[(1, 18446744073709551557), (0, 1)]).unwrap();
+        assert_eq!((x, m), (1, 18446744073709551557));
+        for a in 0..12u64 {
+            for b in 0..18u64 {
+                let expected = (0..36).find(|x| x % 12 == a && x % 18 == b).map(|x| (x, 36));
+                assert_eq!(crt(&[(a, 12), (b, 18)]), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn modint_test1() {
+        let a = Mint::new(P - 1);
+        let b = Mint::from(5u64);
+        assert_eq!(a + b, Mint::new(4));
+        assert_eq!(b - a, Mint::new(6));
+        assert_eq!(a * a, Mint::new(1));
+        assert_eq!(-Mint::new(0), Mint::new(0));
+        assert_eq!(-b, Mint::from(-5i64));
+        assert_eq!(b / b, Mint::new(1));
+        assert_eq!((Mint::new(1) / Mint::new(3)).value(), 333333336);
+        assert_eq!(Mint::new(2).pow(P - 1), Mint::new(1));
+        assert_eq!(Mint::new(P), Mint::new(0));
+        assert_eq!(Mint::new(0).inverse(), None);
+        assert_eq!(format!("{}", a), "1000000006");
+        assert_eq!(ModInt::<{u64::MAX}>::from(-1i64).value(), u64::MAX - 1);
+
+        let mut x = Mint::new(10);
+        x += Mint::new(5);
+        x -= Mint::new(20);
+        x *= Mint::new(2);
+        x /= Mint::new(5);
+        assert_eq!(x, Mint::new(P - 2));
+    }
+
+    #[test]
+    fn modint_fraction_test1() {
+        assert_eq!(Mint::from_fraction(&Fraction {n: 1, d: 2}), Some(Mint::new(500000004)));
+        assert_eq!(Mint::from_fraction(&Fraction {n: -1, d: 2}), Some(-Mint::new(500000004)));
+        assert_eq!(ModInt::<6>::from_fraction(&Fraction {n: 1, d: 2}), None);
+        // Equal fractions have the same residue, reduced or not.
+        let f = Fraction {n: 10, d: 15};
+        let g = Fraction {n: 2, d: 3};
+        assert_eq!(Mint::from_fraction(&f), Mint::from_fraction(&g));
+        let h = Fraction {n: BigInt::from(10).pow(30), d: BigInt::from(3)};
+        assert_eq!(Mint::from_fraction(&h).unwrap() * Mint::new(3), Mint::from(BigInt::from(10).pow(30)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn modint_div_test1() {
+        let _ = ModInt::<10>::new(3) / ModInt::new(4);
+    }
+}
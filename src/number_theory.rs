@@ -0,0 +1,307 @@
+use crate::integer::Integer;
+use crate::prime::factorize_u64;
+use crate::sieve::primes_up_to;
+
+// Greatest common divisor with Euclid's algorithm, always non-negative.
+// gcd(0, 0) is 0.
+pub fn gcd<T: Integer>(a: T, b: T) -> T {
+    let (mut a, mut b) = (a, b);
+    while !b.is_zero() {
+        let r = a % b.clone();
+        a = b;
+        b = r;
+    }
+    if a.is_negative() { T::zero() - a } else { a }
+}
+
+pub fn gcd_many<T: Integer>(l: &[T]) -> T {
+    let mut g = T::zero();
+    for x in l {
+        g = gcd(g, x.clone());
+        if g.is_one() {
+            break;
+        }
+    }
+    g
+}
+
+// Stein's algorithm, only shifts and subtractions.
+pub fn binary_gcd(mut a: u64, mut b: u64) -> u64 {
+    if a == 0 {
+        return b;
+    }
+    if b == 0 {
+        return a;
+    }
+    let shift = (a | b).trailing_zeros();
+    a >>= a.trailing_zeros();
+    loop {
+        b >>= b.trailing_zeros();
+        if a > b {
+            std::mem::swap(&mut a, &mut b);
+        }
+        b -= a;
+        if b == 0 {
+            return a << shift;
+        }
+    }
+}
+
+// Returns (g, x, y) with a * x + b * y == g and g == gcd(a, b). The
+// coefficients can be negative, so T must be a signed type.
+pub fn extended_gcd<T: Integer>(a: T, b: T) -> (T, T, T) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (T::one(), T::zero());
+    let (mut old_y, mut y) = (T::zero(), T::one());
+
+    while !r.is_zero() {
+        let q = old_r.clone() / r.clone();
+        (old_r, r) = (r.clone(), old_r - q.clone() * r);
+        (old_x, x) = (x.clone(), old_x - q.clone() * x);
+        (old_y, y) = (y.clone(), old_y - q * y);
+    }
+
+    if old_r.is_negative() {
+        (T::zero() - old_r, T::zero() - old_x, T::zero() - old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+// Multiplicative and additive functions of n >= 1, from the factorization
+// of n. Each has a range version returning the values for 0..=n in one
+// sieve pass over the primes; index 0 holds 0.
+
+fn factors(n: u64) -> Vec<(u64, u32)> {
+    assert!(n > 0, "not defined for 0");
+    factorize_u64(n)
+}
+
+// Euler's phi: how many of 1..=n are coprime to n.
+pub fn totient(n: u64) -> u64 {
+    factors(n).iter().fold(n, |phi, &(p, _)| phi / p * (p - 1))
+}
+
+// Moebius mu: 0 if a square divides n, else -1 to the number of primes.
+pub fn mobius(n: u64) -> i8 {
+    let f = factors(n);
+    if f.iter().any(|&(_, e)| e > 1) {
+        0
+    } else if f.len().is_multiple_of(2) {
+        1
+    } else {
+        -1
+    }
+}
+
+// lambda(p^e) for a prime power: phi(p^e), but half that for 2^e, e >= 3.
+fn carmichael_prime_power(p: u64, e: u32) -> u64 {
+    if p == 2 && e >= 3 {
+        1 << (e - 2)
+    } else {
+        p.pow(e - 1) * (p - 1)
+    }
+}
+
+// Carmichael's lambda: the smallest m with a^m = 1 mod n for every a coprime
+// to n.
+pub fn carmichael(n: u64) -> u64 {
+    factors(n).iter().fold(1, |l, &(p, e)| {
+        let x = carmichael_prime_power(p, e);
+        l / gcd(l, x) * x
+    })
+}
+
+// Liouville's lambda: -1 to the number of prime factors with repetition.
+pub fn liouville(n: u64) -> i8 {
+    if big_omega(n).is_multiple_of(2) { 1 } else { -1 }
+}
+
+// omega: distinct prime factors, 12 = 2^2 * 3 has 2.
+pub fn omega(n: u64) -> u32 {
+    factors(n).len() as u32
+}
+
+// Omega: prime factors with repetition, 12 has 3.
+pub fn big_omega(n: u64) -> u32 {
+    factors(n).iter().map(|&(_, e)| e).sum()
+}
+
+// The product of the distinct primes dividing n.
+pub fn radical(n: u64) -> u64 {
+    factors(n).iter().map(|&(p, _)| p).product()
+}
+
+// Calls f(m, p, e) for every m in 1..=n and every prime power p^e that
+// exactly divides it.
+fn for_prime_powers(n: u64, mut f: impl FnMut(usize, u64, u32)) {
+    for p in primes_up_to(n) {
+        let (mut pk, mut e) = (p, 1);
+        loop {
+            for m in (pk..=n).step_by(pk as usize) {
+                if !(m / pk).is_multiple_of(p) {
+                    f(m as usize, p, e);
+                }
+            }
+            match pk.checked_mul(p) {
+                Some(next) if next <= n => (pk, e) = (next, e + 1),
+                _ => break,
+            }
+        }
+    }
+}
+
+// A vector for 0..=n, 0 at index 0 and one everywhere else.
+fn ones<T: Integer>(n: u64) -> Vec<T> {
+    let mut v = vec![T::one(); n as usize + 1];
+    v[0] = T::zero();
+    v
+}
+
+pub fn totient_up_to(n: u64) -> Vec<u64> {
+    let mut phi: Vec<u64> = (0..=n).collect();
+    for p in primes_up_to(n) {
+        for m in (p..=n).step_by(p as usize) {
+            phi[m as usize] = phi[m as usize] / p * (p - 1);
+        }
+    }
+    phi
+}
+
+pub fn mobius_up_to(n: u64) -> Vec<i8> {
+    let mut mu: Vec<i8> = ones(n);
+    for_prime_powers(n, |m, _, e| mu[m] = if e > 1 { 0 } else { -mu[m] });
+    mu
+}
+
+pub fn carmichael_up_to(n: u64) -> Vec<u64> {
+    let mut l = ones(n);
+    for_prime_powers(n, |m, p, e| {
+        let x = carmichael_prime_power(p, e);
+        l[m] = l[m] / gcd(l[m], x) * x;
+    });
+    l
+}
+
+pub fn liouville_up_to(n: u64) -> Vec<i8> {
+    let mut v: Vec<i8> = big_omega_up_to(n).iter().map(|&k| if k.is_multiple_of(2) { 1 } else { -1 }).collect();
+    v[0] = 0;
+    v
+}
+
+pub fn omega_up_to(n: u64) -> Vec<u32> {
+    let mut v = vec![0; n as usize + 1];
+    for_prime_powers(n, |m, _, _| v[m] += 1);
+    v
+}
+
+pub fn big_omega_up_to(n: u64) -> Vec<u32> {
+    let mut v = vec![0; n as usize + 1];
+    for_prime_powers(n, |m, _, e| v[m] += e);
+    v
+}
+
+pub fn radical_up_to(n: u64) -> Vec<u64> {
+    let mut v = ones(n);
+    for_prime_powers(n, |m, p, _| v[m] *= p);
+    v
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bigint::BigInt;
+
+    #[test]
+    fn gcd_test1() {
+        assert_eq!(gcd(10, 15), 5);
+        assert_eq!(gcd(-10, 15), 5);
+        assert_eq!(gcd(10, -15), 5);
+        assert_eq!(gcd(0, 7), 7);
+        assert_eq!(gcd(7, 0), 7);
+        assert_eq!(gcd(0, 0), 0);
+        assert_eq!(gcd(17u8, 13), 1);
+        assert_eq!(gcd(BigInt::from(2).pow(100), BigInt::from(6).pow(40)), BigInt::from(2).pow(40));
+    }
+
+    #[test]
+    fn gcd_many_test1() {
+        assert_eq!(gcd_many(&[12, 18, 30]), 6);
+        assert_eq!(gcd_many(&[12, -18, 35]), 1);
+        assert_eq!(gcd_many::<i32>(&[]), 0);
+    }
+
+    #[test]
+    fn binary_gcd_test1() {
+        assert_eq!(binary_gcd(48, 180), 12);
+        assert_eq!(binary_gcd(0, 9), 9);
+        assert_eq!(binary_gcd(9, 0), 9);
+        assert_eq!(binary_gcd(12200160415121876738, 7540113804746346429), 1);
+        assert_eq!(binary_gcd(1 << 40, 3 << 20), 1 << 20);
+        for a in 0..60u64 {
+            for b in 0..60u64 {
+                assert_eq!(binary_gcd(a, b), gcd(a, b));
+            }
+        }
+    }
+
+    #[test]
+    fn extended_gcd_test1() {
+        for (a, b) in [(240, 46), (46, 240), (-240, 46), (17, 5), (0, 5), (5, 0), (-7, -21)] {
+            let (g, x, y) = extended_gcd(a, b);
+            assert_eq!(g, gcd(a, b));
+            assert_eq!(a * x + b * y, g);
+        }
+        assert_eq!(extended_gcd(240i64, 46), (2, -9, 47));
+    }
+
+    #[test]
+    fn arithmetic_functions_test1() {
+        assert_eq!(totient(1), 1);
+        assert_eq!(totient(36), 12);
+        assert_eq!(totient(97), 96);
+        assert_eq!(totient(u64::MAX), 2 * 4 * 16 * 256 * 640 * 65536 * 6700416);
+        assert_eq!((1..=12).map(mobius).collect::<Vec<_>>(), vec![1, -1, -1, 0, -1, 1, -1, 0, 0, 1, -1, 0]);
+        assert_eq!(carmichael(8), 2);
+        assert_eq!(carmichael(15), 4);
+        assert_eq!(carmichael(561), 80);
+        assert_eq!(carmichael(1 << 20), 1 << 18);
+        assert_eq!(liouville(12), -1);
+        assert_eq!(liouville(36), 1);
+        assert_eq!((omega(360), big_omega(360), radical(360)), (3, 6, 30));
+        assert_eq!((omega(1), big_omega(1), radical(1), liouville(1)), (0, 0, 1, 1));
+        assert_eq!(radical(1 << 63), 2);
+    }
+
+    #[test]
+    fn arithmetic_functions_up_to_test1() {
+        let n = 3000;
+        let phi = totient_up_to(n);
+        let mu = mobius_up_to(n);
+        let l = carmichael_up_to(n);
+        let liou = liouville_up_to(n);
+        let (w, big_w, rad) = (omega_up_to(n), big_omega_up_to(n), radical_up_to(n));
+        assert_eq!(phi.len(), n as usize + 1);
+        assert_eq!((phi[0], mu[0], l[0], liou[0], w[0], big_w[0], rad[0]), (0, 0, 0, 0, 0, 0, 0));
+        for m in 1..=n {
+            let i = m as usize;
+            assert_eq!(phi[i], totient(m), "{}", m);
+            assert_eq!(mu[i], mobius(m), "{}", m);
+            assert_eq!(l[i], carmichael(m), "{}", m);
+            assert_eq!(liou[i], liouville(m), "{}", m);
+            assert_eq!((w[i], big_w[i], rad[i]), (omega(m), big_omega(m), radical(m)), "{}", m);
+        }
+        // Every a coprime to m has order dividing lambda(m).
+        for m in 2..200u64 {
+            for a in (1..m).filter(|&a| gcd(a, m) == 1) {
+                let mut x = 1;
+                for _ in 0..l[m as usize] {
+                    x = x * a % m;
+                }
+                assert_eq!(x, 1);
+            }
+        }
+        assert_eq!(totient_up_to(0), vec![0]);
+        assert_eq!(mobius_up_to(1), vec![0, 1]);
+    }
+}
@@ -0,0 +1,290 @@
+use crate::error::FractionError;
+use crate::fraction::Fraction;
+use crate::integer::Integer;
+
+// Small cursor over user input. Errors point at the byte where parsing stopped.
+pub(crate) struct Parser<'a> {
+    s: &'a [u8],
+    pub pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    pub fn new(s: &'a str) -> Parser<'a> {
+        Parser { s: s.as_bytes(), pos: 0 }
+    }
+
+    pub fn error(&self) -> FractionError {
+        FractionError::ParseError { position: self.pos }
+    }
+
+    pub fn peek(&self) -> Option<u8> {
+        self.s.get(self.pos).copied()
+    }
+
+    pub fn eat(&mut self, c: u8) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn expect(&mut self, c: u8) -> Result<(), FractionError> {
+        if self.eat(c) { Ok(()) } else { Err(self.error()) }
+    }
+
+    pub fn skip_ws(&mut self) -> bool {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_whitespace()) {
+            self.pos += 1;
+        }
+        self.pos > start
+    }
+
+    pub fn at_digit(&self) -> bool {
+        self.peek().is_some_and(|c| c.is_ascii_digit())
+    }
+
+    // Only trailing whitespace may be left.
+    pub fn end(&mut self) -> Result<(), FractionError> {
+        self.skip_ws();
+        if self.pos < self.s.len() { Err(self.error()) } else { Ok(()) }
+    }
+
+    // Returns true for '-'.
+    pub fn sign(&mut self) -> bool {
+        if self.eat(b'-') {
+            return true;
+        }
+        self.eat(b'+');
+        false
+    }
+
+    // Appends the digits to acc and returns how many digits were read. At
+    // least one digit is required.
+    pub fn digits<T: Integer>(&mut self, acc: &mut T, negative: bool) -> Result<u32, FractionError> {
+        let digits = self.span();
+        if digits.is_empty() {
+            return Err(self.error());
+        }
+        push_digits(acc, digits, negative)?;
+        Ok(digits.len() as u32)
+    }
+
+    // Skips the digits at the cursor and returns them.
+    fn span(&mut self) -> &'a [u8] {
+        let start = self.pos;
+        while self.at_digit() {
+            self.pos += 1;
+        }
+        &self.s[start..self.pos]
+    }
+
+    pub fn integer<T: Integer>(&mut self, negative: bool) -> Result<T, FractionError> {
+        let mut v = T::zero();
+        self.digits(&mut v, negative)?;
+        Ok(v)
+    }
+
+    // "12" or "12.5" as n / 10^k, not reduced. A repeating part is written
+    // "0.1(6)" or "0.1666...", and 0.a(b) is read as (ab - a) / (10^k (10^p - 1))
+    // where k and p are the lengths of a and b.
+    pub fn decimal<T: Integer>(&mut self, negative: bool) -> Result<Fraction<T>, FractionError> {
+        let mut n = T::zero();
+        self.digits(&mut n, negative)?;
+        if !self.eat(b'.') {
+            return Ok(Fraction {n, d: T::one()});
+        }
+
+        let fixed = self.span();
+        let (fixed, repetend) = if self.eat(b'(') {
+            let repetend = self.span();
+            if repetend.is_empty() {
+                return Err(self.error());
+            }
+            self.expect(b')')?;
+            (fixed, repetend)
+        } else if !fixed.is_empty() && self.s[self.pos..].starts_with(b"...") {
+            self.pos += 3;
+            split_repetend(fixed)
+        } else if fixed.is_empty() {
+            return Err(self.error());
+        } else {
+            (fixed, &[][..])
+        };
+
+        push_digits(&mut n, fixed, negative)?;
+        let d = pow10::<T>(fixed.len() as u32)?;
+        if repetend.is_empty() {
+            return Ok(Fraction {n, d});
+        }
+        let mut ab = n.clone();
+        push_digits(&mut ab, repetend, negative)?;
+        let nines = pow10::<T>(repetend.len() as u32)? - T::one();
+        Ok(Fraction {
+            n: ab.checked_sub(&n).ok_or(FractionError::Overflow)?,
+            d: d.checked_mul(&nines).ok_or(FractionError::Overflow)?,
+        })
+    }
+
+    // Accepts "3/4", "-2 1/3", "1 3/8", "5", "0.125" and "0.1(6)", with optional spaces
+    // around the '/'.
+    pub fn fraction<T: Integer>(&mut self) -> Result<Fraction<T>, FractionError> {
+        self.skip_ws();
+        let negative = self.sign();
+        let start = self.pos;
+        let whole = self.decimal::<T>(negative)?;
+        if self.s[start..self.pos].contains(&b'.') {
+            return Ok(whole);
+        }
+        let whole = whole.n;
+
+        let before_ws = self.pos;
+        let had_ws = self.skip_ws();
+        if self.eat(b'/') {
+            self.skip_ws();
+            let d = self.denominator()?;
+            return Ok(Fraction {n: whole, d});
+        }
+
+        if had_ws && self.at_digit() {
+            let n: T = self.integer(negative)?;
+            self.skip_ws();
+            self.expect(b'/')?;
+            self.skip_ws();
+            let d = self.denominator()?;
+            let n = whole.checked_mul(&d).and_then(|w| w.checked_add(&n)).ok_or(FractionError::Overflow)?;
+            return Ok(Fraction {n, d});
+        }
+
+        self.pos = before_ws;
+        Ok(Fraction {n: whole, d: T::one()})
+    }
+
+    fn denominator<T: Integer>(&mut self) -> Result<T, FractionError> {
+        let d: T = self.integer(false)?;
+        if d.is_zero() {
+            return Err(FractionError::ZeroDenominator);
+        }
+        Ok(d)
+    }
+}
+
+// acc * 10 + digit for each digit, or minus the digit when negative so the
+// whole range of T is reachable.
+fn push_digits<T: Integer>(acc: &mut T, digits: &[u8], negative: bool) -> Result<(), FractionError> {
+    let ten = T::from_u8(10);
+    for c in digits {
+        let digit = T::from_u8(c - b'0');
+        let shifted = acc.checked_mul(&ten).ok_or(FractionError::Overflow)?;
+        *acc = if negative { shifted.checked_sub(&digit) } else { shifted.checked_add(&digit) }
+            .ok_or(FractionError::Overflow)?;
+    }
+    Ok(())
+}
+
+// Guesses the repetend of "0.1666..." or "0.142857142857...": the shortest
+// block whose last two copies end the digits (a single digit if there is
+// none), started as early as possible. Returns the fixed digits and the block.
+fn split_repetend(digits: &[u8]) -> (&[u8], &[u8]) {
+    let k = digits.len();
+    let p = (1..=k / 2).find(|&p| digits[k - 2 * p..k - p] == digits[k - p..]).unwrap_or(1);
+    let mut m = k - p;
+    while m > 0 && digits[m - 1] == digits[m - 1 + p] {
+        m -= 1;
+    }
+    (&digits[..m], &digits[m..m + p])
+}
+
+pub(crate) fn pow10<T: Integer>(k: u32) -> Result<T, FractionError> {
+    let ten = T::from_u8(10);
+    let mut r = T::one();
+    for _ in 0..k {
+        r = r.checked_mul(&ten).ok_or(FractionError::Overflow)?;
+    }
+    Ok(r)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bigint::BigInt;
+    use crate::error::FractionError;
+    use crate::fraction::{Fraction, FractionMixed};
+
+    fn parts<T: Clone>(f: &Fraction<T>) -> (T, T) {
+        (f.n.clone(), f.d.clone())
+    }
+
+    #[test]
+    fn fraction_test1() {
+        assert_eq!(parts(&"3/4".parse::<Fraction>().unwrap()), (3, 4));
+        assert_eq!(parts(&"-3/4".parse::<Fraction>().unwrap()), (-3, 4));
+        assert_eq!(parts(&" 6 / 8 ".parse::<Fraction>().unwrap()), (6, 8));
+        assert_eq!(parts(&"+5".parse::<Fraction>().unwrap()), (5, 1));
+        assert_eq!(parts(&"0.125".parse::<Fraction>().unwrap()), (125, 1000));
+        assert_eq!(parts(&"-2.5".parse::<Fraction>().unwrap()), (-25, 10));
+    }
+
+    #[test]
+    fn fraction_mixed_number_test1() {
+        assert_eq!(parts(&"1 3/8".parse::<Fraction>().unwrap()), (11, 8));
+        assert_eq!(parts(&"-2 1/3".parse::<Fraction>().unwrap()), (-7, 3));
+        assert_eq!(parts(&"2  1 / 3".parse::<Fraction>().unwrap()), (7, 3));
+    }
+
+    #[test]
+    fn fraction_error_test1() {
+        assert_eq!("".parse::<Fraction>(), Err(FractionError::ParseError { position: 0 }));
+        assert_eq!("3/x".parse::<Fraction>(), Err(FractionError::ParseError { position: 2 }));
+        assert_eq!("3/".parse::<Fraction>(), Err(FractionError::ParseError { position: 2 }));
+        assert_eq!("3/-4".parse::<Fraction>(), Err(FractionError::ParseError { position: 2 }));
+        assert_eq!("1 3".parse::<Fraction>(), Err(FractionError::ParseError { position: 3 }));
+        assert_eq!("1 -3/8".parse::<Fraction>(), Err(FractionError::ParseError { position: 2 }));
+        assert_eq!("1.5/2".parse::<Fraction>(), Err(FractionError::ParseError { position: 3 }));
+        assert_eq!("1.".parse::<Fraction>(), Err(FractionError::ParseError { position: 2 }));
+        assert_eq!("3/4 x".parse::<Fraction>(), Err(FractionError::ParseError { position: 4 }));
+        assert_eq!("3/0".parse::<Fraction>(), Err(FractionError::ZeroDenominator));
+    }
+
+    #[test]
+    fn fraction_range_test1() {
+        assert_eq!(parts(&"-128/127".parse::<Fraction<i8>>().unwrap()), (-128, 127));
+        assert_eq!("128/1".parse::<Fraction<i8>>(), Err(FractionError::Overflow));
+        assert_eq!("-1/2".parse::<Fraction<u8>>(), Err(FractionError::Overflow));
+        assert_eq!("1.001".parse::<Fraction<i8>>(), Err(FractionError::Overflow));
+        let big = "123456789012345678901234567890/7".parse::<Fraction<BigInt>>().unwrap();
+        assert_eq!(big.n, "123456789012345678901234567890".parse().unwrap());
+    }
+
+    #[test]
+    fn repeating_test1() {
+        assert_eq!(parts(&"0.1(6)".parse::<Fraction>().unwrap()), (15, 90));
+        assert_eq!(parts(&"0.(142857)".parse::<Fraction>().unwrap()), (142857, 999999));
+        assert_eq!(parts(&"-1.(3)".parse::<Fraction>().unwrap()), (-12, 9));
+        assert_eq!(parts(&"2.5(0)".parse::<Fraction>().unwrap()), (225, 90));
+        assert_eq!("0.1666...".parse::<Fraction>(), "0.1(6)".parse::<Fraction>());
+        assert_eq!("0.142857142857...".parse::<Fraction>(), "0.(142857)".parse::<Fraction>());
+        assert_eq!("0.1232323...".parse::<Fraction>(), "0.1(23)".parse::<Fraction>());
+        assert_eq!("0.12...".parse::<Fraction>(), "0.1(2)".parse::<Fraction>());
+        assert_eq!("0.(9)".parse::<Fraction>(), Ok(Fraction {n: 1, d: 1}));
+    }
+
+    #[test]
+    fn repeating_error_test1() {
+        assert_eq!("0.()".parse::<Fraction>(), Err(FractionError::ParseError { position: 3 }));
+        assert_eq!("0.(3".parse::<Fraction>(), Err(FractionError::ParseError { position: 4 }));
+        assert_eq!("0....".parse::<Fraction>(), Err(FractionError::ParseError { position: 2 }));
+        assert_eq!("0.3..".parse::<Fraction>(), Err(FractionError::ParseError { position: 3 }));
+        assert_eq!("0.(3)/2".parse::<Fraction>(), Err(FractionError::ParseError { position: 5 }));
+        assert_eq!("0.(1234567891)".parse::<Fraction<i32>>(), Err(FractionError::Overflow));
+    }
+
+    #[test]
+    fn fraction_mixed_test1() {
+        assert_eq!("1 3/8".parse::<FractionMixed>(), Ok(FractionMixed {num_improprio: 1, fracao: Fraction {n: 3, d: 8}}));
+        assert_eq!("-2 1/3".parse::<FractionMixed>(), Ok(FractionMixed {num_improprio: -2, fracao: Fraction {n: -1, d: 3}}));
+        assert_eq!("7/4".parse::<FractionMixed>(), Ok(FractionMixed {num_improprio: 1, fracao: Fraction {n: 3, d: 4}}));
+        assert_eq!("1 3/x".parse::<FractionMixed>(), Err(FractionError::ParseError { position: 4 }));
+    }
+}
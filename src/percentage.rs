@@ -1,29 +1,254 @@
+pub mod finance;
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+use crate::bigint::BigInt;
+use crate::error::FractionError;
+use crate::format::FractionStyle;
 use crate::fraction::Fraction;
+use crate::integer::Integer;
+use crate::number_theory::gcd;
+use crate::parse::Parser;
 
 pub fn get_fraction(x: f64) -> Fraction {
-    let mut perc = x;
-    let mut perc_trunc = perc.trunc();
-    let mut i_pow = 0;
+    match checked_get_fraction(x) {
+        Ok(f) => f,
+        Err(e) => panic!("{}", e),
+    }
+}
+
+// Whole numbers are read as percentages (15.0 is 15/100, 1.0 is 1/100),
+// anything with a fractional part is already a ratio (0.085 is 85/1000, 1.5
+// is 3/2). The value goes through its shortest decimal form, so 0.3 is 3/10
+// and not the double nearest to it. Always reduced.
+pub fn checked_get_fraction(x: f64) -> Result<Fraction, FractionError> {
+    let mut f: Fraction = Fraction::from_f64_decimal(x)?;
+    if f.d.is_one() {
+        f.d = 100;
+        f.simplify();
+    }
+    Ok(f)
+}
+
+// "12.5%" (the '%' is optional) as the ratio 125/1000, or "25bp" as
+// 25/10000, not reduced. Repeating decimals like "33.(3)%" are accepted.
+pub fn parse_percentage<T: Integer>(s: &str) -> Result<Fraction<T>, FractionError> {
+    let mut p = Parser::new(s);
+    p.skip_ws();
+    let negative = p.sign();
+    let mut f = p.decimal::<T>(negative)?;
+    p.skip_ws();
+    let scale = if p.eat(b'b') {
+        p.expect(b'p')?;
+        p.eat(b's');
+        T::from_u8(100).checked_mul(&T::from_u8(100))
+    } else {
+        p.eat(b'%');
+        Some(T::from_u8(100))
+    };
+    p.end()?;
+    f.d = scale.and_then(|scale| f.d.checked_mul(&scale)).ok_or(FractionError::Overflow)?;
+    Ok(f)
+}
+
+// f * 100 and f / 100, cancelling common factors first so that small types
+// like i8 (where 100 * 100 doesn't fit) only fail when the result doesn't.
+fn times_hundred<T: Integer>(f: &Fraction<T>) -> Result<Fraction<T>, FractionError> {
+    let hundred = T::from_u8(100);
+    let g = gcd(hundred.clone(), f.d.clone());
+    let n = f.n.checked_mul(&(hundred / g.clone())).ok_or(FractionError::Overflow)?;
+    Ok(Fraction {n, d: f.d.clone() / g})
+}
+
+fn over_hundred<T: Integer>(f: &Fraction<T>) -> Result<Fraction<T>, FractionError> {
+    let hundred = T::from_u8(100);
+    let g = gcd(hundred.clone(), f.n.clone());
+    let d = f.d.checked_mul(&(hundred / g.clone())).ok_or(FractionError::Overflow)?;
+    Ok(Fraction {n: f.n.clone() / g, d})
+}
+
+fn unwrap_or_panic<T>(r: Result<T, FractionError>) -> T {
+    match r {
+        Ok(v) => v,
+        Err(e) => panic!("{}", e),
+    }
+}
+
+// An exact percentage, kept as the ratio it stands for (12.5% is 1/8) and
+// always reduced. Like the Fraction operators, the operations panic when a
+// result doesn't fit in T.
+#[derive(Debug, Clone)]
+pub struct Percentage<T = i32> {
+    ratio: Fraction<T>,
+}
+
+impl<T: Integer> Percentage<T> {
+    pub fn from_ratio(ratio: Fraction<T>) -> Percentage<T> {
+        let mut ratio = ratio;
+        ratio.simplify();
+        Percentage {ratio}
+    }
+
+    // 15 is 15%.
+    pub fn from_percent(percent: Fraction<T>) -> Percentage<T> {
+        Percentage::from_ratio(unwrap_or_panic(over_hundred(&percent)))
+    }
+
+    // 25 is 0.25%.
+    pub fn from_basis_points(bp: Fraction<T>) -> Percentage<T> {
+        Percentage::from_ratio(unwrap_or_panic(over_hundred(&bp).and_then(|f| over_hundred(&f))))
+    }
+
+    pub fn ratio(&self) -> &Fraction<T> {
+        &self.ratio
+    }
+
+    pub fn percent(&self) -> Fraction<T> {
+        unwrap_or_panic(times_hundred(&self.ratio))
+    }
+
+    pub fn basis_points(&self) -> Fraction<T> {
+        unwrap_or_panic(self.checked_basis_points())
+    }
+
+    fn checked_basis_points(&self) -> Result<Fraction<T>, FractionError> {
+        times_hundred(&times_hundred(&self.ratio)?)
+    }
 
-    while (perc - perc_trunc <= 0.0) == false {
-        perc = (perc * 10.0 * 1000.0).round() / 1000.0; // because 0.14 * 10 is 1.4000000000000001, and it bug the calc -_-
-        perc_trunc = perc.trunc();
-        i_pow = i_pow + 1;
+    // 15% of 80 is 12.
+    pub fn of(&self, amount: &Fraction<T>) -> Fraction<T> {
+        amount * &self.ratio
     }
 
-    let mut f = Fraction {n: perc as i32, d: 100};
-    if i_pow > 1 {
-        println!("{}", i_pow);
-        f.d = 10_i32.pow(i_pow);
+    // Markup: 80 increased by 15% is 92.
+    pub fn increase(&self, amount: &Fraction<T>) -> Fraction<T> {
+        amount * &(Fraction::from(T::one()) + &self.ratio)
+    }
+
+    // Discount: 80 decreased by 15% is 68.
+    pub fn decrease(&self, amount: &Fraction<T>) -> Fraction<T> {
+        amount * &(Fraction::from(T::one()) - &self.ratio)
+    }
+
+    // Reverse percentage: the price before a 15% markup that ended at 92 was
+    // 80. Fails for -100%, where every original ends at zero.
+    pub fn before_increase(&self, amount: &Fraction<T>) -> Result<Fraction<T>, FractionError> {
+        let factor = Fraction::from(T::one()) + &self.ratio;
+        if factor.n.is_zero() {
+            return Err(FractionError::ZeroDenominator);
+        }
+        Ok(amount / &factor)
+    }
+
+    // The price before a 15% discount that ended at 68 was 80. Fails for 100%.
+    pub fn before_decrease(&self, amount: &Fraction<T>) -> Result<Fraction<T>, FractionError> {
+        let factor = Fraction::from(T::one()) - &self.ratio;
+        if factor.n.is_zero() {
+            return Err(FractionError::ZeroDenominator);
+        }
+        Ok(amount / &factor)
+    }
+
+    // Relative change from one value to another: 80 to 92 is +15%.
+    pub fn change(from: &Fraction<T>, to: &Fraction<T>) -> Result<Percentage<T>, FractionError> {
+        if from.n.is_zero() {
+            return Err(FractionError::ZeroDenominator);
+        }
+        let mut from = from.clone();
+        from.simplify();
+        Ok(Percentage::from_ratio((to - &from) / from))
+    }
+
+    // Difference in percentage points: from 4% to 5% is 1 point (and a 25%
+    // change).
+    pub fn points_to(&self, other: &Percentage<T>) -> Fraction<T> {
+        other.percent() - self.percent()
+    }
+
+    pub fn add_points(&self, points: &Fraction<T>) -> Percentage<T> {
+        Percentage::from_percent(self.percent() + points)
+    }
+
+    // One change after another: +10% then -10% is -1%.
+    pub fn then(&self, next: &Percentage<T>) -> Percentage<T> {
+        let one = Fraction::from(T::one());
+        let factor = (&one + &self.ratio) * (&one + &next.ratio);
+        Percentage::from_ratio(factor - one)
+    }
+
+    // The same change applied `times` times: 10% three times is 33.1%.
+    pub fn compound(&self, times: u32) -> Percentage<T> {
+        let mut total = Percentage::from_ratio(Fraction::from(T::zero()));
+        for _ in 0..times {
+            total = total.then(self);
+        }
+        total
+    }
+}
+
+impl<T: Integer> PartialEq for Percentage<T> {
+    fn eq(&self, other: &Percentage<T>) -> bool {
+        self.ratio == other.ratio
+    }
+}
+
+impl<T: Integer> Eq for Percentage<T> {}
+
+impl<T: Integer> PartialOrd for Percentage<T> {
+    fn partial_cmp(&self, other: &Percentage<T>) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Integer> Ord for Percentage<T> {
+    fn cmp(&self, other: &Percentage<T>) -> Ordering {
+        self.ratio.cmp(&other.ratio)
+    }
+}
+
+impl<T: Integer + Hash> Hash for Percentage<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.ratio.hash(state);
+    }
+}
+
+// "12.5%", exact with repeating digits like "33.(3)%", or rounded with
+// {:.2}. {:#} writes basis points, "1250bp".
+impl<T: Integer> fmt::Display for Percentage<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = if f.alternate() { self.checked_basis_points() } else { times_hundred(&self.ratio) };
+        // Too big for T, like 50% in basis points for an i8, or T::MIN that
+        // can't be negated below.
+        let value = value.ok().filter(|v| !v.n.is_negative() || v.n.checked_neg().is_some());
+        let Some(value) = value else {
+            let big = Percentage {ratio: Fraction {n: BigInt::from_integer(&self.ratio.n), d: BigInt::from_integer(&self.ratio.d)}};
+            return fmt::Display::fmt(&big, f);
+        };
+        let unit = if f.alternate() { "bp" } else { "%" };
+        let negative = value.n.is_negative();
+        let value = if negative { -value } else { value };
+        let body = match f.precision() {
+            Some(precision) => format!("{:.*}{}", precision, value, unit),
+            None => format!("{}{}", value.format(FractionStyle::Repeating), unit),
+        };
+        f.pad_integral(!negative, "", &body)
     }
+}
+
+impl<T: Integer> FromStr for Percentage<T> {
+    type Err = FractionError;
 
-    //f.simplify();
-    f
+    fn from_str(s: &str) -> Result<Percentage<T>, FractionError> {
+        Ok(Percentage::from_ratio(parse_percentage(s)?))
+    }
 }
 
 #[cfg(test)]
 mod get_fraction {
-    use crate::{percentage::get_fraction, fraction::Fraction};
+    use crate::{percentage::{get_fraction, checked_get_fraction}, fraction::Fraction, error::FractionError};
 
 
     #[test]
@@ -51,18 +276,184 @@ mod get_fraction {
         assert_eq!(get_fraction(0.14), Fraction {n: 14, d: 100});
     }
 
-    // panic!!  |
-    //         \ /
+    #[test]
+    fn checked_test1() {
+        assert_eq!(checked_get_fraction(0.085), Ok(Fraction {n: 85, d: 1000}));
+        assert_eq!(checked_get_fraction(f64::NAN), Err(FractionError::NotRepresentable));
+        assert_eq!(checked_get_fraction(f64::INFINITY), Err(FractionError::NotRepresentable));
+        assert_eq!(checked_get_fraction(1e12), Err(FractionError::Overflow));
+    }
+
     #[test]
     fn test6() {
-        assert_eq!(get_fraction(0.3), Fraction {n: 14, d: 100});
+        assert_eq!(get_fraction(0.3), Fraction {n: 3, d: 10});
     }
 
     #[test]
     fn test7() {
-        assert_eq!(get_fraction(33.0), Fraction {n: 14, d: 100});
+        assert_eq!(get_fraction(33.0), Fraction {n: 33, d: 100});
+    }
+
+    #[test]
+    fn around_one_test1() {
+        let parts = |x: f64| {
+            let f = get_fraction(x);
+            (f.n, f.d)
+        };
+        assert_eq!(parts(0.99), (99, 100));
+        assert_eq!(parts(1.0), (1, 100));
+        assert_eq!(parts(1.01), (101, 100));
+        assert_eq!(parts(1.5), (3, 2));
+        assert_eq!(parts(2.0), (1, 50));
+        assert_eq!(parts(-1.0), (-1, 100));
+    }
+
+    #[test]
+    fn reduced_test1() {
+        let f = get_fraction(0.08);
+        assert_eq!((f.n, f.d), (2, 25));
+        let f = get_fraction(15.0);
+        assert_eq!((f.n, f.d), (3, 20));
+        let f = get_fraction(-1.25);
+        assert_eq!((f.n, f.d), (-5, 4));
+        assert_eq!(checked_get_fraction(0.1 + 0.2), Err(FractionError::Overflow));
+    }
+
+}
+
+#[cfg(test)]
+mod parse_percentage {
+    use crate::{percentage::parse_percentage, fraction::Fraction, error::FractionError};
+
+    fn parts(f: Fraction) -> (i32, i32) {
+        (f.n, f.d)
+    }
+
+    #[test]
+    fn test1() {
+        assert_eq!(parts(parse_percentage("12.5%").unwrap()), (125, 1000));
+        assert_eq!(parts(parse_percentage("15%").unwrap()), (15, 100));
+        assert_eq!(parts(parse_percentage(" -3.75 % ").unwrap()), (-375, 10000));
+        assert_eq!(parts(parse_percentage("40").unwrap()), (40, 100));
+    }
+
+    #[test]
+    fn test2() {
+        assert_eq!(parse_percentage::<i32>("12,5%"), Err(FractionError::ParseError { position: 2 }));
+        assert_eq!(parse_percentage::<i32>("%"), Err(FractionError::ParseError { position: 0 }));
+        assert_eq!(parse_percentage::<i32>("12%%"), Err(FractionError::ParseError { position: 3 }));
+        assert_eq!(parse_percentage::<i8>("12.5%"), Err(FractionError::Overflow));
+        assert_eq!(parse_percentage::<i32>("12b"), Err(FractionError::ParseError { position: 3 }));
+        assert_eq!(parse_percentage::<i8>("1bp"), Err(FractionError::Overflow));
+    }
+
+    #[test]
+    fn test3() {
+        assert_eq!(parts(parse_percentage("25bp").unwrap()), (25, 10000));
+        assert_eq!(parts(parse_percentage("2.5 bps").unwrap()), (25, 100000));
+        assert_eq!(parts(parse_percentage("33.(3)%").unwrap()), (300, 900));
+    }
+}
+
+#[cfg(test)]
+mod percentage_type {
+    use crate::{percentage::Percentage, fraction::Fraction, error::FractionError};
+
+    fn pct(s: &str) -> Percentage {
+        s.parse().unwrap()
+    }
+
+    fn int(n: i32) -> Fraction {
+        Fraction::from(n)
+    }
+
+    #[test]
+    fn small_types_test1() {
+        let p: Percentage<i8> = Percentage::from_basis_points(Fraction::from(100));
+        assert_eq!(p.ratio(), &Fraction {n: 1, d: 100});
+        assert_eq!(p.basis_points(), Fraction::from(100));
+        assert_eq!(p.percent(), Fraction::from(1));
+        assert_eq!(format!("{:#}", p), "100bp");
+        let half: Percentage<i8> = "50%".parse().unwrap();
+        assert_eq!(format!("{:#}", half), "5000bp");
+        assert_eq!(format!("{}", half), "50%");
+        let p: Percentage<i8> = Percentage::from_ratio(Fraction {n: -32, d: 25});
+        assert_eq!(format!("{}", p), "-128%");
+        assert_eq!(format!("{:#}", p), "-12800bp");
+        let p: Percentage<u8> = Percentage::from_basis_points(Fraction::from(250));
+        assert_eq!(p.ratio(), &Fraction {n: 1, d: 40});
+        assert_eq!(format!("{:#}", p), "250bp");
+        let p: Percentage<i16> = Percentage::from_basis_points(Fraction::from(1));
+        assert_eq!(format!("{:.2}", p), "0.01%");
+        assert_eq!("1bp".parse::<Percentage<i8>>().err(), Some(FractionError::Overflow));
+    }
+
+    #[test]
+    fn constructors_test1() {
+        assert_eq!(Percentage::from_percent(int(15)), pct("15%"));
+        assert_eq!(Percentage::from_ratio(Fraction {n: 1, d: 8}), pct("12.5%"));
+        assert_eq!(Percentage::from_basis_points(int(25)), pct("0.25%"));
+        assert_eq!((pct("12.5%").ratio().n, pct("12.5%").ratio().d), (1, 8));
+        assert_eq!(pct("0.25%").basis_points(), int(25));
+        assert_eq!(pct("-3%").percent(), int(-3));
+    }
+
+    #[test]
+    fn of_test1() {
+        assert_eq!(pct("15%").of(&int(80)), int(12));
+        assert_eq!(pct("12.5%").of(&int(10)), Fraction {n: 5, d: 4});
+        assert_eq!(pct("15%").increase(&int(80)), int(92));
+        assert_eq!(pct("15%").decrease(&int(80)), int(68));
+    }
+
+    #[test]
+    fn reverse_test1() {
+        assert_eq!(pct("15%").before_increase(&int(92)), Ok(int(80)));
+        assert_eq!(pct("15%").before_decrease(&int(68)), Ok(int(80)));
+        assert_eq!(pct("20%").before_decrease(&int(100)), Ok(int(125)));
+        assert_eq!(pct("100%").before_decrease(&int(0)), Err(FractionError::ZeroDenominator));
+        assert_eq!(pct("-100%").before_increase(&int(0)), Err(FractionError::ZeroDenominator));
+    }
+
+    #[test]
+    fn change_test1() {
+        assert_eq!(Percentage::change(&int(80), &int(92)), Ok(pct("15%")));
+        assert_eq!(Percentage::change(&int(92), &int(80)), Ok(Percentage::from_ratio(Fraction {n: -3, d: 23})));
+        assert_eq!(Percentage::change(&int(-4), &int(-2)), Ok(pct("-50%")));
+        assert_eq!(Percentage::change(&int(0), &int(5)), Err(FractionError::ZeroDenominator));
+    }
+
+    #[test]
+    fn points_test1() {
+        assert_eq!(pct("4%").points_to(&pct("5%")), int(1));
+        assert_eq!(Percentage::change(&int(4), &int(5)), Ok(pct("25%")));
+        assert_eq!(pct("4%").add_points(&Fraction {n: 1, d: 2}), pct("4.5%"));
+    }
+
+    #[test]
+    fn compound_test1() {
+        assert_eq!(pct("10%").then(&pct("-10%")), pct("-1%"));
+        assert_eq!(pct("10%").compound(3), pct("33.1%"));
+        assert_eq!(pct("10%").compound(0), pct("0%"));
+        assert_eq!(pct("50%").then(&pct("-50%")), pct("-25%"));
+    }
+
+    #[test]
+    fn display_test1() {
+        assert_eq!(pct("12.5%").to_string(), "12.5%");
+        assert_eq!(pct("-3%").to_string(), "-3%");
+        assert_eq!(Percentage::from_ratio(Fraction {n: 1, d: 3}).to_string(), "33.(3)%");
+        assert_eq!(format!("{:.2}", Percentage::from_ratio(Fraction {n: 2, d: 3})), "66.67%");
+        assert_eq!(format!("{:#}", pct("12.5%")), "1250bp");
+        assert_eq!(format!("{:>7}", pct("-7.5%")), "  -7.5%");
+        assert_eq!(pct(&pct("33.(3)%").to_string()), Percentage::from_ratio(Fraction {n: 1, d: 3}));
     }
-    //         / \
-    // panic!!  |
 
-}
\ No newline at end of file
+    #[test]
+    fn cmp_test1() {
+        assert!(pct("12.5%") < pct("13%"));
+        assert!(pct("-1%") < pct("0%"));
+        assert_eq!("10 %".parse::<Percentage>(), Ok(pct("10%")));
+        assert_eq!("x".parse::<Percentage>(), Err(FractionError::ParseError { position: 0 }));
+    }
+}
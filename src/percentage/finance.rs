@@ -0,0 +1,220 @@
+use crate::error::FractionError;
+use crate::fraction::{from_u64, Fraction};
+use crate::integer::Integer;
+use crate::percentage::Percentage;
+
+// Everything is exact, round only when showing the result ({:.2}). Rates
+// are yearly and split evenly over the compounding periods of a year. A
+// count of zero periods or payments fails with ZeroDenominator, and any
+// value that doesn't fit in T with Overflow; the numbers grow quickly with
+// the number of periods, so long loans want i128 or BigInt.
+
+fn int<T: Integer>(v: u32) -> Result<Fraction<T>, FractionError> {
+    from_u64::<T>(v as u64).map(Fraction::from)
+}
+
+// Checked versions of the operators, reduced like theirs.
+fn add<T: Integer>(a: &Fraction<T>, b: &Fraction<T>) -> Result<Fraction<T>, FractionError> {
+    let mut r = a.clone();
+    r.checked_sum(b)?;
+    r.simplify();
+    Ok(r)
+}
+
+fn sub<T: Integer>(a: &Fraction<T>, b: &Fraction<T>) -> Result<Fraction<T>, FractionError> {
+    let mut r = a.clone();
+    r.checked_sub(b)?;
+    r.simplify();
+    Ok(r)
+}
+
+fn mul<T: Integer>(a: &Fraction<T>, b: &Fraction<T>) -> Result<Fraction<T>, FractionError> {
+    let mut r = a.clone();
+    r.checked_mul(b)?;
+    r.simplify();
+    Ok(r)
+}
+
+fn div<T: Integer>(a: &Fraction<T>, b: &Fraction<T>) -> Result<Fraction<T>, FractionError> {
+    let mut r = a.clone();
+    r.checked_div(b)?;
+    r.simplify();
+    Ok(r)
+}
+
+fn period_rate<T: Integer>(rate: &Percentage<T>, periods_per_year: u32) -> Result<Fraction<T>, FractionError> {
+    if periods_per_year == 0 {
+        return Err(FractionError::ZeroDenominator);
+    }
+    div(rate.ratio(), &int(periods_per_year)?)
+}
+
+// (1 + i)^periods
+fn growth<T: Integer>(i: &Fraction<T>, periods: u32) -> Result<Fraction<T>, FractionError> {
+    let mut g = add(&Fraction::from(T::one()), i)?;
+    if periods == 0 {
+        return Ok(Fraction::from(T::one()));
+    }
+    g.checked_pow(i32::try_from(periods).map_err(|_| FractionError::Overflow)?)?;
+    Ok(g)
+}
+
+// principal * rate * years
+pub fn simple_interest<T: Integer>(principal: &Fraction<T>, rate: &Percentage<T>, years: &Fraction<T>) -> Result<Fraction<T>, FractionError> {
+    mul(&mul(principal, rate.ratio())?, years)
+}
+
+pub fn simple_amount<T: Integer>(principal: &Fraction<T>, rate: &Percentage<T>, years: &Fraction<T>) -> Result<Fraction<T>, FractionError> {
+    add(principal, &simple_interest(principal, rate, years)?)
+}
+
+// principal * (1 + rate / periods_per_year)^(periods_per_year * years)
+pub fn compound_amount<T: Integer>(principal: &Fraction<T>, rate: &Percentage<T>, periods_per_year: u32, years: u32) -> Result<Fraction<T>, FractionError> {
+    let periods = periods_per_year.checked_mul(years).ok_or(FractionError::Overflow)?;
+    mul(principal, &growth(&period_rate(rate, periods_per_year)?, periods)?)
+}
+
+pub fn compound_interest<T: Integer>(principal: &Fraction<T>, rate: &Percentage<T>, periods_per_year: u32, years: u32) -> Result<Fraction<T>, FractionError> {
+    sub(&compound_amount(principal, rate, periods_per_year, years)?, principal)
+}
+
+// The yearly rate that gives the same growth compounded once a year: 12%
+// compounded monthly is about 12.68%.
+pub fn effective_annual_rate<T: Integer>(rate: &Percentage<T>, periods_per_year: u32) -> Result<Percentage<T>, FractionError> {
+    let g = growth(&period_rate(rate, periods_per_year)?, periods_per_year)?;
+    Ok(Percentage::from_ratio(sub(&g, &Fraction::from(T::one()))?))
+}
+
+// Fixed payment that repays a loan in `payments` periods:
+// principal * i / (1 - (1 + i)^-payments), with i the rate per period, or
+// an even split without interest.
+pub fn payment<T: Integer>(principal: &Fraction<T>, rate: &Percentage<T>, periods_per_year: u32, payments: u32) -> Result<Fraction<T>, FractionError> {
+    if payments == 0 {
+        return Err(FractionError::ZeroDenominator);
+    }
+    let i = period_rate(rate, periods_per_year)?;
+    if i.n.is_zero() {
+        return div(principal, &int(payments)?);
+    }
+    let g = growth(&i, payments)?;
+    // g - 1 is 0 only when g = (-1)^payments = 1, at -200% per period.
+    div(&mul(&mul(principal, &i)?, &g)?, &sub(&g, &Fraction::from(T::one()))?)
+}
+
+#[derive(Debug, Clone)]
+pub struct Installment<T> {
+    // 1 for the first payment.
+    pub period: u32,
+    pub payment: Fraction<T>,
+    pub interest: Fraction<T>,
+    pub principal: Fraction<T>,
+    // Still owed after this payment, exactly zero after the last one.
+    pub balance: Fraction<T>,
+}
+
+// Fixed-payment (French) schedule: each payment first covers the interest
+// on what is still owed and the rest repays the loan.
+pub fn amortisation_schedule<T: Integer>(
+    principal: &Fraction<T>,
+    rate: &Percentage<T>,
+    periods_per_year: u32,
+    payments: u32,
+) -> Result<Vec<Installment<T>>, FractionError> {
+    let i = period_rate(rate, periods_per_year)?;
+    let amount = payment(principal, rate, periods_per_year, payments)?;
+    let mut balance = principal.clone();
+    let mut schedule = Vec::with_capacity(payments as usize);
+    for period in 1..=payments {
+        let interest = mul(&balance, &i)?;
+        let repaid = sub(&amount, &interest)?;
+        balance = sub(&balance, &repaid)?;
+        schedule.push(Installment {period, payment: amount.clone(), interest, principal: repaid, balance: balance.clone()});
+    }
+    Ok(schedule)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bigint::BigInt;
+
+    fn pct<T: Integer>(s: &str) -> Percentage<T> {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn simple_test1() {
+        let p = Fraction::from(1000);
+        assert_eq!(simple_interest(&p, &pct("5%"), &Fraction::from(3)).unwrap(), Fraction::from(150));
+        assert_eq!(simple_amount(&p, &pct("5%"), &Fraction {n: 1, d: 2}).unwrap(), Fraction::from(1025));
+    }
+
+    #[test]
+    fn compound_test1() {
+        let p = Fraction::from(1000i128);
+        let a = compound_amount(&p, &pct("12%"), 12, 1).unwrap();
+        assert_eq!(a, Fraction {n: 1000 * 101i128.pow(12), d: 100i128.pow(12)});
+        assert_eq!(format!("{:.2}", a), "1126.83");
+        assert_eq!(format!("{:.2}", compound_interest(&p, &pct("12%"), 12, 1).unwrap()), "126.83");
+        assert_eq!(compound_amount(&p, &pct("10%"), 1, 2).unwrap(), Fraction::from(1210));
+        assert_eq!(compound_amount(&p, &pct("10%"), 1, 0).unwrap(), p);
+    }
+
+    #[test]
+    fn effective_rate_test1() {
+        assert_eq!(format!("{:.4}", effective_annual_rate(&pct::<i128>("12%"), 12).unwrap()), "12.6825%");
+        assert_eq!(effective_annual_rate(&pct::<i64>("10%"), 2).unwrap(), pct("10.25%"));
+        assert_eq!(effective_annual_rate(&pct::<i64>("10%"), 1).unwrap(), pct("10%"));
+    }
+
+    #[test]
+    fn payment_test1() {
+        let p = Fraction::from(BigInt::from(1000));
+        assert_eq!(format!("{:.2}", payment(&p, &pct("12%"), 12, 12).unwrap()), "88.85");
+        assert_eq!(payment(&p, &pct("0%"), 12, 8).unwrap(), Fraction::from(BigInt::from(125)));
+        assert_eq!(payment(&Fraction::from(1000), &pct("10%"), 1, 1).unwrap(), Fraction::from(1100));
+    }
+
+    #[test]
+    fn schedule_test1() {
+        let p = Fraction::from(BigInt::from(1000));
+        let schedule = amortisation_schedule(&p, &pct("12%"), 12, 12).unwrap();
+        assert_eq!(schedule.len(), 12);
+        assert_eq!(schedule[0].interest, Fraction::from(BigInt::from(10)));
+        assert_eq!(format!("{:.2}", schedule[0].principal), "78.85");
+        assert_eq!(schedule[11].balance, Fraction::from(BigInt::from(0)));
+        let repaid = schedule.iter().fold(Fraction::from(BigInt::from(0)), |acc, x| acc + &x.principal);
+        assert_eq!(repaid, p);
+        for x in &schedule {
+            assert_eq!(&x.interest + &x.principal, x.payment);
+        }
+    }
+
+    #[test]
+    fn schedule_bigint_test1() {
+        // 5 year loan, 6% compounded monthly.
+        let p = Fraction::from(BigInt::from(20000));
+        let schedule = amortisation_schedule(&p, &pct("6%"), 12, 60).unwrap();
+        assert_eq!(format!("{:.2}", schedule[0].payment), "386.66");
+        assert_eq!(format!("{:.2}", schedule[0].interest), "100.00");
+        assert_eq!(schedule[59].balance, Fraction::from(BigInt::from(0)));
+    }
+
+    #[test]
+    fn error_test1() {
+        let p = Fraction::from(1000);
+        assert_eq!(compound_amount(&p, &pct("5%"), 0, 1).err(), Some(FractionError::ZeroDenominator));
+        assert_eq!(compound_amount(&p, &pct("5%"), 1 << 20, 1 << 20).err(), Some(FractionError::Overflow));
+        assert_eq!(compound_amount(&p, &pct("5%"), 12, 30).err(), Some(FractionError::Overflow));
+        assert_eq!(effective_annual_rate(&pct::<i32>("5%"), 0).err(), Some(FractionError::ZeroDenominator));
+        assert_eq!(payment(&p, &pct("5%"), 12, 0).err(), Some(FractionError::ZeroDenominator));
+        assert_eq!(payment(&p, &pct("0%"), 12, 0).err(), Some(FractionError::ZeroDenominator));
+        assert_eq!(payment(&p, &pct("5%"), 0, 12).err(), Some(FractionError::ZeroDenominator));
+        assert_eq!(payment(&p, &pct("-200%"), 1, 2).err(), Some(FractionError::ZeroDenominator));
+        assert_eq!(amortisation_schedule(&p, &pct("5%"), 12, 0).err(), Some(FractionError::ZeroDenominator));
+        // 300 periods don't fit in an i8.
+        let small = Fraction::from(10i8);
+        assert_eq!(payment(&small, &pct("0%"), 1, 300).err(), Some(FractionError::Overflow));
+        assert_eq!(compound_amount(&small, &pct("0%"), 300, 1).err(), Some(FractionError::Overflow));
+    }
+}
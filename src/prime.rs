@@ -1,53 +1,533 @@
-pub fn next_prime(p: i32) -> i32 {
-    if p < 2 {
-        return 2;
+use crate::bigint::BigInt;
+use crate::error::FractionError;
+use crate::fraction::Fraction;
+use crate::integer::Integer;
+use crate::modular::{add_mod_u128, mul_mod, pow_mod, Montgomery};
+use crate::number_theory::gcd;
+use crate::sieve::{next_prime_u64, primes_up_to, Primes};
+
+pub fn next_prime<T: Integer>(p: T) -> T {
+    match checked_next_prime(p) {
+        Ok(x) => x,
+        Err(e) => panic!("{}", e),
     }
-    let mut x = if p % 2 == 0 { p + 1 } else { p + 2 };
-    loop {
-        if is_prime(x) {
-            break;
-        }
-        x += 2;
+}
+
+pub fn checked_next_prime<T: Integer>(p: T) -> Result<T, FractionError> {
+    let two = T::from_u8(2);
+    if p < two {
+        return Ok(two);
+    }
+    // Past the last u64 prime, wider types go on below.
+    if let Some(x) = p.to_u64().and_then(next_prime_u64) {
+        return T::from_u64(x).ok_or(FractionError::Overflow);
+    }
+
+    let step = if (p.clone() % two.clone()).is_zero() { T::one() } else { two.clone() };
+    let mut x = p.checked_add(&step).ok_or(FractionError::Overflow)?;
+    while !is_prime(x.clone()) {
+        x = x.checked_add(&two).ok_or(FractionError::Overflow)?;
     }
-    x
+    Ok(x)
 }
 
-pub fn is_prime(x: i32) -> bool {
-    if x % 2 == 0 && x > 2 {
+// 0, 1 and negative numbers are not prime. Values that fit in a u64 go
+// through the deterministic test below, larger ones (u128, BigInt) through
+// Miller-Rabin with more bases, which is only proven correct below 3.3e24.
+// u128 values skip BigInt and use Montgomery multiplication.
+pub fn is_prime<T: Integer>(x: T) -> bool {
+    if x < T::from_u8(2) {
         return false;
     }
-    if x == 1 {
+    if let Some(n) = x.to_u64() {
+        return is_prime_u64(n);
+    }
+    let x = BigInt::from_integer(&x);
+    match x.to_integer::<u128>() {
+        Some(n) => is_prime_u128(n),
+        None => is_prime_big(&x),
+    }
+}
+
+const SMALL_PRIMES: [u64; 15] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47];
+
+// The first 12 primes are enough witnesses for every n < 3.18e23, so for all
+// of u64.
+const WITNESSES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+// Deterministic Miller-Rabin. Small factors are ruled out first, which also
+// settles every n < 47^2.
+pub fn is_prime_u64(n: u64) -> bool {
+    if n < 2 {
         return false;
     }
+    for p in SMALL_PRIMES {
+        if n.is_multiple_of(p) {
+            return n == p;
+        }
+    }
+    if n < 47 * 47 {
+        return true;
+    }
 
-    for i in (3..x).step_by(2) {
-        if x % i == 0 {
-            return false;
+    // n - 1 = d * 2^s with d odd.
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    'witness: for a in WITNESSES {
+        let mut x = pow_mod(a, d, n);
+        if x == 1 || x == n - 1 {
+            continue;
+        }
+        for _ in 1..s {
+            x = mul_mod(x, x, n);
+            if x == n - 1 {
+                continue 'witness;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+fn is_prime_big(n: &BigInt) -> bool {
+    let one = BigInt::from(1);
+    for p in SMALL_PRIMES {
+        if (n % &BigInt::from(p)).is_zero() {
+            return *n == BigInt::from(p);
         }
     }
 
+    let n1 = n - &one;
+    let mut d = n1.clone();
+    let mut s = 0;
+    while !d.is_zero() && (&d % &BigInt::from(2)).is_zero() {
+        d = &d / &BigInt::from(2);
+        s += 1;
+    }
+    let pow_mod = |base: &BigInt, exp: &BigInt| {
+        let (mut r, mut base, mut exp) = (one.clone(), base % n, exp.clone());
+        let two = BigInt::from(2);
+        while !exp.is_zero() {
+            let (q, bit) = exp.divrem(&two);
+            if !bit.is_zero() {
+                r = &(&r * &base) % n;
+            }
+            base = &(&base * &base) % n;
+            exp = q;
+        }
+        r
+    };
+
+    'witness: for a in SMALL_PRIMES.iter().chain(&[53, 59, 61, 67, 71]) {
+        let mut x = pow_mod(&BigInt::from(*a), &d);
+        if x == one || x == n1 {
+            continue;
+        }
+        for _ in 1..s {
+            x = &(&x * &x) % n;
+            if x == n1 {
+                continue 'witness;
+            }
+        }
+        return false;
+    }
     true
 }
 
-pub fn divs_of_num(num: i32) -> Vec<i32> {
-    let mut v = vec![0; 0];
+// Miller-Rabin with the 20 bases of is_prime_big, without going through BigInt.
+fn is_prime_u128(n: u128) -> bool {
+    if let Ok(n) = u64::try_from(n) {
+        return is_prime_u64(n);
+    }
+    for p in SMALL_PRIMES {
+        if n.is_multiple_of(p as u128) {
+            return false;
+        }
+    }
+    let mont = Montgomery::new(n);
+    let (one, minus_one) = (mont.encode(1), mont.encode(n - 1));
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    'witness: for a in SMALL_PRIMES.iter().chain(&[53, 59, 61, 67, 71]) {
+        let mut x = mont.pow(mont.encode(*a as u128), d);
+        if x == one || x == minus_one {
+            continue;
+        }
+        for _ in 1..s {
+            x = mont.mul(x, x);
+            if x == minus_one {
+                continue 'witness;
+            }
+        }
+        return false;
+    }
+    true
+}
 
-    v.push(1);
+// Pollard's rho with Brent's cycle detection on x -> x^2 + c, for an odd
+// composite n. Returns a proper factor, or None when this c fails.
+fn brent(n: u128, c: u128) -> Option<u128> {
+    // Below 2^64 the plain u128 product is enough and cheaper.
+    let mont = if n > u64::MAX as u128 { Some(Montgomery::new(n)) } else { None };
+    let mul = |a: u128, b: u128| match &mont {
+        Some(mont) => mont.mul(a, b),
+        None => a * b % n,
+    };
+    let f = |x: u128| add_mod_u128(mul(x, x), c, n);
 
-    for x in 2..num{
-        if num % x == 0 {
-            v.push(x);
+    // Steps between gcds.
+    const BATCH: u64 = 128;
+    let (mut y, mut x, mut ys) = (2 % n, 0, 0);
+    let (mut g, mut q, mut r) = (1, 1, 1u64);
+    while g == 1 {
+        x = y;
+        for _ in 0..r {
+            y = f(y);
+        }
+        let mut k = 0;
+        while k < r && g == 1 {
+            ys = y;
+            for _ in 0..BATCH.min(r - k) {
+                y = f(y);
+                q = mul(q, x.abs_diff(y));
+            }
+            g = gcd(q, n);
+            k += BATCH;
         }
+        r *= 2;
     }
+    if g == n {
+        // The batch overshot, redo it one step at a time.
+        loop {
+            ys = f(ys);
+            g = gcd(x.abs_diff(ys), n);
+            if g > 1 {
+                break;
+            }
+        }
+    }
+    if g == n { None } else { Some(g) }
+}
 
-    v.push(num);
+// Prime factors of n > 1 with repetition, in no particular order.
+fn split(n: u128, out: &mut Vec<u128>) {
+    if is_prime_u128(n) {
+        out.push(n);
+        return;
+    }
+    let d = (1..).find_map(|c| brent(n, c)).unwrap();
+    split(d, out);
+    split(n / d, out);
+}
 
+// Prime factorization as (prime, exponent) pairs in increasing order:
+// 360 is [(2, 3), (3, 2), (5, 1)]. Small factors are found by trial division,
+// the rest with Pollard-Brent rho and Miller-Rabin. 0 and 1 give an empty
+// list.
+pub fn factorize_u128(mut n: u128) -> Vec<(u128, u32)> {
+    let mut factors = Vec::new();
+    if n < 2 {
+        return factors;
+    }
+    TRIAL_PRIMES.with(|primes| {
+        for &p in primes {
+            let p = p as u128;
+            if p * p > n {
+                break;
+            }
+            let mut e = 0;
+            while n.is_multiple_of(p) {
+                n /= p;
+                e += 1;
+            }
+            if e > 0 {
+                factors.push((p, e));
+            }
+        }
+    });
+    let mut rest = Vec::new();
+    if n > 1 {
+        split(n, &mut rest);
+    }
+    rest.sort_unstable();
+    for p in rest {
+        match factors.last_mut() {
+            Some((q, e)) if *q == p => *e += 1,
+            _ => factors.push((p, 1)),
+        }
+    }
+    factors
+}
+
+pub fn factorize_u64(n: u64) -> Vec<(u64, u32)> {
+    factorize_u128(n as u128).into_iter().map(|(p, e)| (p as u64, e)).collect()
+}
+
+thread_local! {
+    static TRIAL_PRIMES: Vec<u64> = primes_up_to(1000);
+}
+
+// Factorization of |n|, for any n whose absolute value fits in a u128. Fails
+// with NotRepresentable for 0 and for anything bigger.
+pub fn checked_factorize<T: Integer>(n: T) -> Result<Vec<(T, u32)>, FractionError> {
+    let n = BigInt::from_integer(&n);
+    let n = if n < BigInt::zero() { -n } else { n };
+    match n.to_integer::<u128>() {
+        Some(0) | None => Err(FractionError::NotRepresentable),
+        // Every factor is at most |n|, and |T::MIN| is a power of 2.
+        Some(n) => Ok(factorize_u128(n)
+            .into_iter()
+            .map(|(p, e)| (BigInt::from(p).to_integer().unwrap(), e))
+            .collect()),
+    }
+}
+
+pub fn factorize<T: Integer>(n: T) -> Vec<(T, u32)> {
+    match checked_factorize(n) {
+        Ok(x) => x,
+        Err(e) => panic!("{}", e),
+    }
+}
+
+// Multiplies a factorization back out.
+pub fn checked_from_factors<T: Integer>(factors: &[(T, u32)]) -> Result<T, FractionError> {
+    let mut n = T::one();
+    for (p, e) in factors {
+        for _ in 0..*e {
+            n = n.checked_mul(p).ok_or(FractionError::Overflow)?;
+        }
+    }
+    Ok(n)
+}
+
+pub fn from_factors<T: Integer>(factors: &[(T, u32)]) -> T {
+    match checked_from_factors(factors) {
+        Ok(x) => x,
+        Err(e) => panic!("{}", e),
+    }
+}
+
+// Walks two factorizations in step, keeping the primes where keep(ea, eb)
+// is not 0.
+fn merge_factors<T: Integer>(a: &[(T, u32)], b: &[(T, u32)], keep: fn(u32, u32) -> u32) -> Vec<(T, u32)> {
+    let (mut i, mut j) = (0, 0);
+    let mut out = Vec::new();
+    while i < a.len() || j < b.len() {
+        let (p, ea, eb) = match (a.get(i), b.get(j)) {
+            (Some((p, ea)), Some((q, _))) if p < q => { i += 1; (p, *ea, 0) }
+            (Some((p, _)), Some((q, eb))) if q < p => { j += 1; (q, 0, *eb) }
+            (Some((p, ea)), Some((_, eb))) => { i += 1; j += 1; (p, *ea, *eb) }
+            (Some((p, ea)), None) => { i += 1; (p, *ea, 0) }
+            (None, Some((q, eb))) => { j += 1; (q, 0, *eb) }
+            (None, None) => unreachable!(),
+        };
+        let e = keep(ea, eb);
+        if e > 0 {
+            out.push((p.clone(), e));
+        }
+    }
+    out
+}
+
+// gcd and lcm of numbers given by their factorizations: the lower and the
+// higher exponent of every prime.
+pub fn gcd_of_factors<T: Integer>(a: &[(T, u32)], b: &[(T, u32)]) -> Vec<(T, u32)> {
+    merge_factors(a, b, u32::min)
+}
+
+pub fn lcm_of_factors<T: Integer>(l: &[Vec<(T, u32)>]) -> Vec<(T, u32)> {
+    l.iter().fold(Vec::new(), |acc, f| merge_factors(&acc, f, u32::max))
+}
+
+impl<T: Integer> Fraction<T> {
+    // simplify() for when the factorizations of |n| and |d| are already
+    // known: divides both by the product of the shared prime powers. The sign
+    // moves to n last, as in simplify, so MIN/-2 works and MIN/-1 panics.
+    pub fn simplify_with_factors(&mut self, n: &[(T, u32)], d: &[(T, u32)]) {
+        let g = from_factors(&gcd_of_factors(n, d));
+        if self.n.is_zero() && !self.d.is_zero() {
+            self.d = T::one();
+        } else if !g.is_one() {
+            self.n = self.n.clone() / g.clone();
+            self.d = self.d.clone() / g;
+        }
+        if let Err(e) = self.normalize_sign() {
+            panic!("{}", e);
+        }
+    }
+}
+
+// All divisors of the number with this factorization, in increasing order.
+pub fn divisors_from_factors(factors: &[(u64, u32)]) -> Vec<u64> {
+    let mut v = vec![1];
+    for &(p, e) in factors {
+        let len = v.len();
+        let mut pk = 1;
+        for _ in 0..e {
+            pk *= p;
+            for i in 0..len {
+                v.push(v[i] * pk);
+            }
+        }
+    }
+    v.sort_unstable();
     v
 }
 
+// The divisor functions below are for n >= 1 and panic on 0.
+pub fn divisors(n: u64) -> Vec<u64> {
+    assert!(n > 0, "0 has infinitely many divisors");
+    divisors_from_factors(&factorize_u64(n))
+}
+
+// tau(n), the number of divisors: the product of e + 1 over p^e.
+pub fn divisor_count(n: u64) -> u64 {
+    assert!(n > 0, "0 has infinitely many divisors");
+    factorize_u64(n).iter().map(|&(_, e)| e as u64 + 1).product()
+}
+
+// sigma_k(n), the sum of the k-th powers of the divisors, as the product of
+// 1 + p^k + p^2k + ... + p^ek over p^e. sigma_0 is tau and sigma_1 the
+// plain divisor sum, which always fits in a u128; larger k can overflow.
+pub fn checked_divisor_sum(n: u64, k: u32) -> Result<u128, FractionError> {
+    assert!(n > 0, "0 has infinitely many divisors");
+    let mut sum = 1u128;
+    for (p, e) in factorize_u64(n) {
+        let pk = (p as u128).checked_pow(k).ok_or(FractionError::Overflow)?;
+        let (mut term, mut power) = (1u128, 1u128);
+        for _ in 0..e {
+            power = power.checked_mul(pk).ok_or(FractionError::Overflow)?;
+            term = term.checked_add(power).ok_or(FractionError::Overflow)?;
+        }
+        sum = sum.checked_mul(term).ok_or(FractionError::Overflow)?;
+    }
+    Ok(sum)
+}
+
+pub fn divisor_sum(n: u64, k: u32) -> u128 {
+    match checked_divisor_sum(n, k) {
+        Ok(x) => x,
+        Err(e) => panic!("{}", e),
+    }
+}
+
+// Sum of the divisors below n, the aliquot sum s(n).
+pub fn proper_divisor_sum(n: u64) -> u128 {
+    divisor_sum(n, 1) - n as u128
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Abundance {
+    // s(n) < n, like every prime.
+    Deficient,
+    // s(n) == n: 6, 28, 496, ...
+    Perfect,
+    // s(n) > n: 12, 18, 20, ...
+    Abundant,
+}
+
+pub fn abundance(n: u64) -> Abundance {
+    match proper_divisor_sum(n).cmp(&(n as u128)) {
+        std::cmp::Ordering::Less => Abundance::Deficient,
+        std::cmp::Ordering::Equal => Abundance::Perfect,
+        std::cmp::Ordering::Greater => Abundance::Abundant,
+    }
+}
+
+// The m != n with s(n) == m and s(m) == n, if n is part of an amicable pair.
+pub fn amicable_partner(n: u64) -> Option<u64> {
+    let m = u64::try_from(proper_divisor_sum(n)).ok()?;
+    (m != n && m > 0 && proper_divisor_sum(m) == n as u128).then_some(m)
+}
+
+// Amicable pairs (a, b) with a < b <= limit, sorted by a. The aliquot sums
+// are sieved (every d adds itself to its multiples), so this takes
+// O(limit log limit) time and a u64 per number up to limit.
+pub fn amicable_pairs(limit: u64) -> Vec<(u64, u64)> {
+    let len = limit as usize + 1;
+    let mut s = vec![0u64; len];
+    for d in 1..len / 2 + 1 {
+        for m in (2 * d..len).step_by(d) {
+            s[m] += d as u64;
+        }
+    }
+    (1..len)
+        .filter_map(|a| {
+            let b = s[a] as usize;
+            (b > a && b < len && s[b] == a as u64).then_some((a as u64, b as u64))
+        })
+        .collect()
+}
+
+// Each divisor once, so 1 gives [1]. 0 and negative numbers keep the old
+// [1, num].
+pub fn divs_of_num(num: i32) -> Vec<i32> {
+    if num < 1 {
+        return vec![1, num];
+    }
+
+    divisors(num as u64).into_iter().map(|d| d as i32).collect()
+}
+
+// pi(x), the number of primes <= x, with Lucy_Hedgehog's method in
+// O(x^(3/4)) time and O(sqrt(x)) memory: S(v) starts as the count of
+// 2..=v and sieving by each prime p <= sqrt(x) removes the numbers whose
+// smallest factor is p, only for the values v = x / k that matter.
+pub fn prime_count(x: u64) -> u64 {
+    if x < 2 {
+        return 0;
+    }
+    let r = x.isqrt() as usize;
+    // small[v] = S(v) for v <= r, large[k] = S(x / k) for k <= r.
+    let mut small: Vec<u64> = (0..=r as u64).map(|v| v.saturating_sub(1)).collect();
+    let mut large: Vec<u64> = (0..=r as u64).map(|k| x.checked_div(k).map_or(0, |v| v - 1)).collect();
+    for p in 2..=r {
+        if small[p] == small[p - 1] {
+            continue;
+        }
+        // Primes below p.
+        let sp = small[p - 1];
+        let p2 = (p * p) as u64;
+        let end = r.min((x / p2) as usize);
+        for k in 1..=end {
+            let d = k * p;
+            let s = if d <= r { large[d] } else { small[(x / d as u64) as usize] };
+            large[k] -= s - sp;
+        }
+        for v in (p * p..=r).rev() {
+            small[v] -= small[v / p] - sp;
+        }
+    }
+    large[1]
+}
+
+// The n-th prime, counting from nth_prime(1) = 2. Large n start from an
+// estimate of p_n, corrected with prime_count until it's just below, then
+// the rest is sieved.
+pub fn nth_prime(n: u64) -> u64 {
+    assert!(n > 0, "primes are counted from 1");
+    if n <= 100_000 {
+        return Primes::new().nth(n as usize - 1).unwrap();
+    }
+    let nf = n as f64;
+    let (ln, lnln) = (nf.ln(), nf.ln().ln());
+    let mut x = (nf * (ln + lnln - 1.0 + (lnln - 2.0) / ln)) as u64;
+    let mut c = prime_count(x);
+    // Aim a little below so the last step only goes forward.
+    while c >= n || n - c > 100_000 {
+        let step = (n as f64 - 1000.0 - c as f64) * (x as f64).ln();
+        x = (x as f64 + step) as u64;
+        c = prime_count(x);
+    }
+    Primes::starting_at(x + 1).nth((n - c - 1) as usize).unwrap()
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::prime::divs_of_num;
+    use crate::bigint::BigInt;
+    use crate::error::FractionError;
+    use crate::fraction::Fraction;
+    use crate::prime::*;
 
     #[test]
     fn divs_of_num_test1() {
@@ -66,4 +546,230 @@ mod tests {
         assert_eq!(divs_of_num(56), vec![1, 2, 4, 7, 8, 14, 28, 56]);
         //assert_eq!(divs_of_num(48), vec![1, 48, 2, 24, 3, 16, 4, 12, 6, 8]);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn bigint_is_prime_test1() {
+        assert!(is_prime(BigInt::from(7919)));
+        assert!(!is_prime(BigInt::from(7917)));
+    }
+
+    #[test]
+    fn bigint_next_prime_test1() {
+        assert_eq!(next_prime(BigInt::from(7907)), BigInt::from(7919));
+        assert_eq!(next_prime(BigInt::from(1)), BigInt::from(2));
+    }
+
+    #[test]
+    fn checked_next_prime_test1() {
+        assert_eq!(checked_next_prime(113i8), Ok(127));
+        assert_eq!(checked_next_prime(127i8), Err(FractionError::Overflow));
+        assert_eq!(checked_next_prime(251u8), Err(FractionError::Overflow));
+        assert_eq!(checked_next_prime(i64::MAX - 30), Ok(9223372036854775783));
+        assert_eq!(checked_next_prime(i64::MAX), Err(FractionError::Overflow));
+        assert_eq!(checked_next_prime(18446744073709551557u64), Err(FractionError::Overflow));
+        assert_eq!(checked_next_prime(18446744073709551557u128), Ok(18446744073709551629));
+        let big = BigInt::from(18446744073709551557u64);
+        assert_eq!(checked_next_prime(big), Ok(BigInt::from(18446744073709551629u128)));
+    }
+
+    #[test]
+    fn is_prime_test1() {
+        assert!(!is_prime(0));
+        assert!(!is_prime(1));
+        assert!(!is_prime(-7));
+        assert!(is_prime(2));
+        assert!(is_prime(127i8));
+        assert!(!is_prime(i64::MIN));
+        let naive = |n: u64| n >= 2 && (2..n).take_while(|d| d * d <= n).all(|d| !n.is_multiple_of(d));
+        for n in 0..20000u64 {
+            assert_eq!(is_prime_u64(n), naive(n), "{}", n);
+        }
+    }
+
+    #[test]
+    fn is_prime_u64_test1() {
+        assert!(is_prime_u64(18446744073709551557));
+        assert!(!is_prime_u64(u64::MAX));
+        assert!(is_prime_u64(4294967291));
+        assert!(!is_prime_u64(4294967291 * 4294967279));
+        // Carmichael numbers and strong pseudoprimes to several bases.
+        for n in [561u128, 41041, 2047, 3215031751, 3825123056546413051, 318665857834031151167461] {
+            assert!(!is_prime(n), "{}", n);
+        }
+    }
+
+    #[test]
+    fn bigint_is_prime_test2() {
+        let two = BigInt::from(2);
+        assert!(is_prime(two.pow(89) - BigInt::from(1)));
+        assert!(!is_prime(two.pow(67) - BigInt::from(1)));
+        assert!(is_prime(two.pow(127) - BigInt::from(1)));
+        assert!(!is_prime(BigInt::from(-7)));
+    }
+
+    #[test]
+    fn next_prime_test1() {
+        assert_eq!(next_prime(-10), 2);
+        assert_eq!(next_prime(2), 3);
+        assert_eq!(next_prime(1_000_000_000_000u64), 1_000_000_000_039);
+        assert_eq!(next_prime(18446744073709551557u64 - 2), 18446744073709551557);
+        assert_eq!(checked_next_prime(18446744073709551557u64), Err(FractionError::Overflow));
+    }
+
+    #[test]
+    fn factorize_test1() {
+        assert_eq!(factorize(360), vec![(2, 3), (3, 2), (5, 1)]);
+        assert_eq!(factorize(-360), vec![(2, 3), (3, 2), (5, 1)]);
+        assert_eq!(factorize(1), vec![]);
+        assert_eq!(factorize(97u8), vec![(97, 1)]);
+        assert_eq!(factorize(i32::MIN), vec![(2, 31)]);
+        assert_eq!(factorize(i8::MIN), vec![(2, 7)]);
+        assert_eq!(checked_factorize(0), Err(FractionError::NotRepresentable));
+        assert_eq!(checked_factorize(BigInt::from(2).pow(128)), Err(FractionError::NotRepresentable));
+        for n in 1..5000u64 {
+            let f = factorize_u64(n);
+            assert!(f.iter().all(|&(p, _)| is_prime_u64(p)));
+            assert!(f.windows(2).all(|w| w[0].0 < w[1].0));
+            assert_eq!(from_factors(&f), n);
+        }
+    }
+
+    #[test]
+    fn factorize_u64_test1() {
+        assert_eq!(factorize_u64(u64::MAX), vec![(3, 1), (5, 1), (17, 1), (257, 1), (641, 1), (65537, 1), (6700417, 1)]);
+        assert_eq!(factorize_u64(4294967291 * 4294967279), vec![(4294967279, 1), (4294967291, 1)]);
+        assert_eq!(factorize_u64(18446744073709551557), vec![(18446744073709551557, 1)]);
+        assert_eq!(factorize_u64(1 << 63), vec![(2, 63)]);
+        assert_eq!(factorize_u64(1000003 * 1000003 * 1009), vec![(1009, 1), (1000003, 2)]);
+    }
+
+    #[test]
+    fn factorize_u128_test1() {
+        assert_eq!(factorize_u128(u128::MAX), vec![(3, 1), (5, 1), (17, 1), (257, 1), (641, 1), (65537, 1), (274177, 1), (6700417, 1), (67280421310721, 1)]);
+        assert_eq!(factorize_u128(1 << 127), vec![(2, 127)]);
+        assert_eq!(factorize_u128((1 << 127) - 1), vec![((1 << 127) - 1, 1)]);
+        let (p, q) = (1_000_000_007u128, 998_244_353u128);
+        assert_eq!(factorize_u128(p * p * q * 4294967291), vec![(q, 1), (p, 2), (4294967291, 1)]);
+        assert_eq!(factorize((1u128 << 67) - 1), vec![(193707721, 1), (761838257287, 1)]);
+    }
+
+    #[test]
+    fn factors_test1() {
+        let a = factorize(360);
+        let b = factorize(84);
+        assert_eq!(from_factors(&gcd_of_factors(&a, &b)), 12);
+        assert_eq!(from_factors(&lcm_of_factors(&[a, b, factorize(11)])), 27720);
+        assert_eq!(lcm_of_factors::<i32>(&[]), vec![]);
+        assert_eq!(checked_from_factors(&[(2i8, 7)]), Err(FractionError::Overflow));
+
+        let mut f = Fraction {n: -360, d: 84};
+        f.simplify_with_factors(&factorize(360), &factorize(84));
+        assert_eq!((f.n, f.d), (-30, 7));
+        let mut f = Fraction {n: 0, d: -84};
+        f.simplify_with_factors(&[], &factorize(84));
+        assert_eq!((f.n, f.d), (0, 1));
+        let mut f = Fraction {n: i32::MIN, d: -2};
+        f.simplify_with_factors(&[(2, 31)], &[(2, 1)]);
+        assert_eq!((f.n, f.d), (1 << 30, 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "arithmetic overflow")]
+    fn simplify_with_factors_min_test1() {
+        Fraction {n: i32::MIN, d: -1}.simplify_with_factors(&[(2, 31)], &[]);
+    }
+
+    #[test]
+    fn divisors_test1() {
+        assert_eq!(divisors(1), vec![1]);
+        assert_eq!(divisors(97), vec![1, 97]);
+        assert_eq!(divisors(360).len(), 24);
+        assert_eq!(divisors(600851475143), vec![1, 71, 839, 1471, 6857, 59569, 104441, 486847, 1234169, 5753023, 10086647, 87625999, 408464633, 716151937, 8462696833, 600851475143]);
+        for n in 1..500u64 {
+            assert_eq!(divisors(n), (1..=n).filter(|d| n.is_multiple_of(*d)).collect::<Vec<_>>());
+        }
+        assert_eq!(divs_of_num(1), vec![1]);
+        assert_eq!(divs_of_num(2), vec![1, 2]);
+        assert_eq!(divs_of_num(49), vec![1, 7, 49]);
+    }
+
+    #[test]
+    fn divisor_functions_test1() {
+        assert_eq!(divisor_count(1), 1);
+        assert_eq!(divisor_count(360), 24);
+        assert_eq!(divisor_count(1 << 63), 64);
+        assert_eq!(divisor_count(963761198400), 6720);
+        assert_eq!(divisor_sum(12, 0), 6);
+        assert_eq!(divisor_sum(12, 1), 28);
+        assert_eq!(divisor_sum(12, 2), 210);
+        assert_eq!(divisor_sum(u64::MAX, 1), 4 * 6 * 18 * 258 * 642 * 65538 * 6700418);
+        assert_eq!(checked_divisor_sum(u64::MAX, 3), Err(FractionError::Overflow));
+        assert_eq!(proper_divisor_sum(1), 0);
+        assert_eq!(proper_divisor_sum(220), 284);
+        for n in 1..300u64 {
+            let d = divisors(n);
+            assert_eq!(divisor_count(n), d.len() as u64);
+            assert_eq!(divisor_sum(n, 2), d.iter().map(|&x| (x * x) as u128).sum());
+        }
+    }
+
+    #[test]
+    fn abundance_test1() {
+        let perfect: Vec<u64> = (1..10000).filter(|&n| abundance(n) == Abundance::Perfect).collect();
+        assert_eq!(perfect, vec![6, 28, 496, 8128]);
+        assert_eq!(abundance(33550336), Abundance::Perfect);
+        assert_eq!(abundance(8589869056), Abundance::Perfect);
+        assert_eq!(abundance(1), Abundance::Deficient);
+        assert_eq!(abundance(18446744073709551557), Abundance::Deficient);
+        assert_eq!(abundance(12), Abundance::Abundant);
+        assert_eq!(abundance(945), Abundance::Abundant);
+    }
+
+    #[test]
+    fn amicable_test1() {
+        assert_eq!(amicable_partner(220), Some(284));
+        assert_eq!(amicable_partner(284), Some(220));
+        assert_eq!(amicable_partner(6), None);
+        assert_eq!(amicable_partner(1), None);
+        assert_eq!(amicable_partner(9363584), Some(9437056));
+        assert_eq!(amicable_pairs(10000), vec![(220, 284), (1184, 1210), (2620, 2924), (5020, 5564), (6232, 6368)]);
+        assert_eq!(amicable_pairs(284), vec![(220, 284)]);
+        assert_eq!(amicable_pairs(283), vec![]);
+        assert_eq!(amicable_pairs(0), vec![]);
+    }
+
+    #[test]
+    fn prime_count_test1() {
+        use crate::sieve::Sieve;
+        let sieve = Sieve::new(200_000);
+        let mut count = 0;
+        for x in 0..=3000 {
+            if x >= 2 && sieve.is_prime(x) {
+                count += 1;
+            }
+            assert_eq!(prime_count(x), count, "{}", x);
+        }
+        for x in [9999, 10000, 65535, 65536, 99991, 123456, 200_000] {
+            assert_eq!(prime_count(x), sieve.primes().take_while(|&p| p <= x).count() as u64, "{}", x);
+        }
+        assert_eq!(prime_count(10_000_000), Sieve::new(10_000_000).count() as u64);
+    }
+
+    #[test]
+    fn prime_count_test2() {
+        assert_eq!(prime_count(1_000_000_000), 50847534);
+        assert_eq!(prime_count(10_000_000_000), 455052511);
+        assert_eq!(prime_count(100_000_000_000), 4118054813);
+    }
+
+    #[test]
+    fn nth_prime_test1() {
+        for (i, p) in Primes::new().take(2000).enumerate() {
+            assert_eq!(nth_prime(i as u64 + 1), p);
+        }
+        assert_eq!(nth_prime(10000), 104729);
+        assert_eq!(nth_prime(100_001), 1299721);
+        assert_eq!(nth_prime(1_000_000), 15485863);
+        assert_eq!(nth_prime(100_000_000), 2038074743);
+    }
+}
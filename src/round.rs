@@ -0,0 +1,169 @@
+use crate::error::FractionError;
+use crate::fraction::{checked_div_floor, Fraction};
+use crate::integer::Integer;
+use crate::parse::pow10;
+
+// How a value between two integers is rounded. The Half* modes go to the
+// nearest integer and only differ on ties: 2.5 and -2.5 become 3 and -2 with
+// HalfUp, 2 and -2 with HalfEven, 3 and -3 with HalfAwayFromZero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rounding {
+    Floor,
+    Ceil,
+    Truncate,
+    HalfUp,
+    HalfEven,
+    HalfAwayFromZero,
+}
+
+impl<T: Integer> Fraction<T> {
+    pub fn round_with(&self, mode: Rounding) -> T {
+        match self.checked_round_with(mode) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    // Works with floor division and compares the remainder with d - r, so
+    // it never needs more than the range of T; nothing is negated, so a
+    // T::MIN numerator or denominator is fine too. The one value past T is
+    // T::MIN / -1, which fails with Overflow.
+    pub fn checked_round_with(&self, mode: Rounding) -> Result<T, FractionError> {
+        if self.d.is_zero() {
+            return Err(FractionError::ZeroDenominator);
+        }
+        let (q, r) = checked_div_floor(&self.n, &self.d).ok_or(FractionError::Overflow)?;
+        if r.is_zero() {
+            return Ok(q);
+        }
+        // r and d - r have the sign of d and add up to it.
+        let rest = self.d.clone() - r.clone();
+        let half = if self.d.is_negative() { rest.cmp(&r) } else { r.cmp(&rest) };
+        let up = match mode {
+            Rounding::Floor => false,
+            Rounding::Ceil => true,
+            Rounding::Truncate => q.is_negative(),
+            _ => match half {
+                std::cmp::Ordering::Less => false,
+                std::cmp::Ordering::Greater => true,
+                std::cmp::Ordering::Equal => match mode {
+                    Rounding::HalfEven => !(q.clone() % T::from_u8(2)).is_zero(),
+                    Rounding::HalfAwayFromZero => !q.is_negative(),
+                    _ => true,
+                },
+            },
+        };
+        Ok(if up { q + T::one() } else { q })
+    }
+
+    // The nearest multiple of 1/k, reduced: 0.3 to the nearest 1/16 is 5/16.
+    pub fn round_to(&self, k: T, mode: Rounding) -> Fraction<T> {
+        match self.checked_round_to(k, mode) {
+            Ok(f) => f,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    pub fn checked_round_to(&self, k: T, mode: Rounding) -> Result<Fraction<T>, FractionError> {
+        if k.is_zero() || self.d.is_zero() {
+            return Err(FractionError::ZeroDenominator);
+        }
+        let scaled = Fraction {n: self.n.checked_mul(&k).ok_or(FractionError::Overflow)?, d: self.d.clone()};
+        let mut f = Fraction {n: scaled.checked_round_with(mode)?, d: k};
+        f.simplify();
+        Ok(f)
+    }
+
+    // 2/3 to 2 places is 67/100.
+    pub fn round_to_decimals(&self, places: u32, mode: Rounding) -> Fraction<T> {
+        match self.checked_round_to_decimals(places, mode) {
+            Ok(f) => f,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    pub fn checked_round_to_decimals(&self, places: u32, mode: Rounding) -> Result<Fraction<T>, FractionError> {
+        self.checked_round_to(pow10(places)?, mode)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bigint::BigInt;
+
+    fn fr(n: i32, d: i32) -> Fraction {
+        Fraction {n, d}
+    }
+
+    fn all(f: Fraction) -> [i32; 6] {
+        [Rounding::Floor, Rounding::Ceil, Rounding::Truncate, Rounding::HalfUp, Rounding::HalfEven, Rounding::HalfAwayFromZero]
+            .map(|mode| f.round_with(mode))
+    }
+
+    #[test]
+    fn round_with_test1() {
+        assert_eq!(all(fr(5, 2)), [2, 3, 2, 3, 2, 3]);
+        assert_eq!(all(fr(-5, 2)), [-3, -2, -2, -2, -2, -3]);
+        assert_eq!(all(fr(7, 2)), [3, 4, 3, 4, 4, 4]);
+        assert_eq!(all(fr(-7, 2)), [-4, -3, -3, -3, -4, -4]);
+        assert_eq!(all(fr(7, 3)), [2, 3, 2, 2, 2, 2]);
+        assert_eq!(all(fr(-7, 3)), [-3, -2, -2, -2, -2, -2]);
+        assert_eq!(all(fr(8, 3)), [2, 3, 2, 3, 3, 3]);
+        assert_eq!(all(fr(6, 3)), [2, 2, 2, 2, 2, 2]);
+        assert_eq!(all(fr(5, -2)), [-3, -2, -2, -2, -2, -3]);
+        assert_eq!(all(fr(1, 2)), [0, 1, 0, 1, 0, 1]);
+        assert_eq!(all(fr(-1, 2)), [-1, 0, 0, 0, 0, -1]);
+    }
+
+    #[test]
+    fn round_with_range_test1() {
+        let f = fr(i32::MAX, 2);
+        assert_eq!(f.round_with(Rounding::HalfEven), 1073741824);
+        assert_eq!(fr(i32::MIN, 3).round_with(Rounding::Floor), -715827883);
+        assert_eq!(fr(i32::MAX, i32::MAX - 1).round_with(Rounding::Ceil), 2);
+        let g: Fraction<u8> = Fraction {n: 255, d: 2};
+        assert_eq!(g.round_with(Rounding::HalfUp), 128);
+        assert_eq!(all(fr(i32::MIN, -2)), [1 << 30; 6]);
+        assert_eq!(all(fr(i32::MIN, -3)), [715827882, 715827883, 715827882, 715827883, 715827883, 715827883]);
+        assert_eq!(all(fr(i32::MIN + 1, -2)), [1073741823, 1073741824, 1073741823, 1073741824, 1073741824, 1073741824]);
+        assert_eq!(all(fr(1, i32::MIN)), [-1, 0, 0, 0, 0, 0]);
+        assert_eq!(all(fr(-1, i32::MIN)), [0, 1, 0, 0, 0, 0]);
+        assert_eq!(all(fr(i32::MAX, i32::MIN)), [-1, 0, 0, -1, -1, -1]);
+        assert_eq!(all(fr(i32::MIN, 1)), [i32::MIN; 6]);
+        assert_eq!(all(fr(i32::MAX, -1)), [-i32::MAX; 6]);
+        assert_eq!(fr(i32::MIN, -1).checked_round_with(Rounding::Floor), Err(FractionError::Overflow));
+        assert_eq!(fr(i32::MIN, -1).checked_round_to(1, Rounding::HalfEven), Err(FractionError::Overflow));
+        assert_eq!(fr(1, 0).checked_round_with(Rounding::Floor), Err(FractionError::ZeroDenominator));
+    }
+
+    #[test]
+    #[should_panic(expected = "arithmetic overflow")]
+    fn round_with_min_test1() {
+        fr(i32::MIN, -1).round_with(Rounding::Ceil);
+    }
+
+    #[test]
+    fn round_to_test1() {
+        assert_eq!(fr(3, 10).round_to(16, Rounding::HalfEven), fr(5, 16));
+        assert_eq!(fr(1, 2).round_to(16, Rounding::HalfEven), fr(8, 16));
+        assert_eq!(fr(1, 2).round_to(16, Rounding::HalfEven).d, 2);
+        assert_eq!(fr(-1, 3).round_to(4, Rounding::Floor), fr(-1, 2));
+        assert_eq!(fr(1, 32).round_to(16, Rounding::HalfEven), fr(0, 1));
+        assert_eq!(fr(3, 32).round_to(16, Rounding::HalfEven), fr(1, 8));
+        assert_eq!(fr(1, 2).checked_round_to(0, Rounding::Floor), Err(FractionError::ZeroDenominator));
+        assert_eq!(fr(i32::MAX, 3).checked_round_to(16, Rounding::Floor), Err(FractionError::Overflow));
+    }
+
+    #[test]
+    fn round_to_decimals_test1() {
+        assert_eq!(fr(2, 3).round_to_decimals(2, Rounding::HalfUp), fr(67, 100));
+        assert_eq!(fr(2, 3).round_to_decimals(2, Rounding::Truncate), fr(66, 100));
+        assert_eq!(fr(-1, 8).round_to_decimals(2, Rounding::HalfEven), fr(-12, 100));
+        assert_eq!(fr(-1, 8).round_to_decimals(2, Rounding::HalfAwayFromZero), fr(-13, 100));
+        assert_eq!(fr(7, 2).round_to_decimals(0, Rounding::HalfEven), fr(4, 1));
+        assert_eq!(fr(1, 3).checked_round_to_decimals(10, Rounding::Floor), Err(FractionError::Overflow));
+        let f = Fraction {n: BigInt::from(1), d: BigInt::from(3)};
+        assert_eq!(f.round_to_decimals(30, Rounding::HalfUp).n, "333333333333333333333333333333".parse().unwrap());
+    }
+}
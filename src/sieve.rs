@@ -0,0 +1,276 @@
+// Sieve of Eratosthenes on a mod 30 wheel: only numbers coprime to 30 are
+// stored, 8 of every 30, one bit each, so primes up to 10^9 take ~33 MB.
+// 2, 3 and 5 are handled apart.
+
+const WHEEL: [u64; 8] = [1, 7, 11, 13, 17, 19, 23, 29];
+
+// Position of n % 30 in WHEEL, or 8 for residues that share a factor with 30.
+const WHEEL_INDEX: [u8; 30] = [
+    8, 0, 8, 8, 8, 8, 8, 1, 8, 8, 8, 2, 8, 3, 8, 8, 8, 4, 8, 5, 8, 8, 8, 6, 8, 8, 8, 8, 8, 7,
+];
+
+// Distance from WHEEL[i] to the next number coprime to 30.
+const WHEEL_GAP: [u64; 8] = [6, 4, 2, 4, 2, 4, 6, 2];
+
+pub struct Sieve {
+    limit: u64,
+    bits: Vec<u8>,
+}
+
+impl Sieve {
+    // All primes up to and including limit.
+    pub fn new(limit: u64) -> Sieve {
+        let mut bits = vec![0xffu8; (limit / 30 + 1) as usize];
+        // 1 is not prime.
+        bits[0] &= !1;
+
+        let mut p = 7;
+        let mut i = 1;
+        while p * p <= limit {
+            if bits[(p / 30) as usize] >> i & 1 == 1 {
+                // Cross out p * q for every q >= p coprime to 30; the others
+                // are not stored.
+                let (mut q, mut j) = (p, i);
+                while p * q <= limit {
+                    let m = p * q;
+                    bits[(m / 30) as usize] &= !(1 << WHEEL_INDEX[(m % 30) as usize]);
+                    q += WHEEL_GAP[j];
+                    j = (j + 1) % 8;
+                }
+            }
+            p += WHEEL_GAP[i];
+            i = (i + 1) % 8;
+        }
+        Sieve {limit, bits}
+    }
+
+    pub fn limit(&self) -> u64 {
+        self.limit
+    }
+
+    // n must not be greater than the limit.
+    pub fn is_prime(&self, n: u64) -> bool {
+        assert!(n <= self.limit, "{} is beyond the sieve limit {}", n, self.limit);
+        match n {
+            2 | 3 | 5 => true,
+            _ => {
+                let i = WHEEL_INDEX[(n % 30) as usize];
+                i < 8 && self.bits[(n / 30) as usize] >> i & 1 == 1
+            }
+        }
+    }
+
+    pub fn primes(&self) -> impl Iterator<Item = u64> + '_ {
+        let small = [2, 3, 5].into_iter().filter(|&p| p <= self.limit);
+        let wheel = self.bits.iter().enumerate().flat_map(|(k, &byte)| {
+            (0..8).filter(move |i| byte >> i & 1 == 1).map(move |i| k as u64 * 30 + WHEEL[i])
+        });
+        small.chain(wheel.take_while(|&p| p <= self.limit))
+    }
+
+    pub fn count(&self) -> usize {
+        self.primes().count()
+    }
+}
+
+pub fn primes_up_to(limit: u64) -> Vec<u64> {
+    Sieve::new(limit).primes().collect()
+}
+
+const SEGMENT: u64 = 1 << 15;
+
+// Numbers in [lo, hi] without a factor in base (base primes themselves are
+// kept). When base holds every prime up to sqrt(hi) these are the primes.
+// Primes up to sqrt(u64::MAX) fit in a u32, half the memory of a u64.
+fn sieve_segment(lo: u64, hi: u64, base: &[u32]) -> Vec<u64> {
+    let mut keep = vec![true; (hi - lo + 1) as usize];
+    for &p in base {
+        let p = p as u64;
+        if p * p > hi {
+            break;
+        }
+        let first = match lo.div_ceil(p).checked_mul(p) {
+            Some(m) => m.max(p * p),
+            None => continue,
+        };
+        let mut m = first;
+        while m <= hi {
+            keep[(m - lo) as usize] = false;
+            m = match m.checked_add(p) {
+                Some(m) => m,
+                None => break,
+            };
+        }
+    }
+    (lo..=hi).zip(keep).filter(|&(n, keep)| keep && n >= 2).map(|(n, _)| n).collect()
+}
+
+// Segmented sieve: the primes in [a, b], using memory for the primes up to
+// sqrt(b) (as u32) plus a fixed-size window.
+pub fn primes_in_range(a: u64, b: u64) -> Vec<u64> {
+    Primes::starting_at(a).take_while(|&p| p <= b).collect()
+}
+
+// Every prime from a starting point on, one segment at a time. The base
+// primes always stop at the square root of the current segment's end.
+pub struct Primes {
+    next: u64,
+    done: bool,
+    base: Vec<u32>,
+    base_limit: u64,
+    buffer: std::vec::IntoIter<u64>,
+}
+
+impl Primes {
+    pub fn new() -> Primes {
+        Primes::starting_at(2)
+    }
+
+    // The primes >= start.
+    pub fn starting_at(start: u64) -> Primes {
+        Primes {next: start, done: false, base: Vec::new(), base_limit: 0, buffer: Vec::new().into_iter()}
+    }
+}
+
+impl Default for Primes {
+    fn default() -> Primes {
+        Primes::new()
+    }
+}
+
+impl Iterator for Primes {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        loop {
+            if let Some(p) = self.buffer.next() {
+                return Some(p);
+            }
+            if self.done {
+                return None;
+            }
+            let lo = self.next;
+            let hi = lo.saturating_add(SEGMENT - 1);
+            let root = hi.isqrt();
+            if root > self.base_limit {
+                // Once the base reaches past the square root of root, the
+                // new base primes come from a segment of their own.
+                if self.base_limit.saturating_mul(self.base_limit) >= root {
+                    let more = sieve_segment(self.base_limit + 1, root, &self.base);
+                    self.base.extend(more.into_iter().map(|p| p as u32));
+                } else {
+                    self.base = Sieve::new(root).primes().map(|p| p as u32).collect();
+                }
+                self.base_limit = root;
+            }
+            self.buffer = sieve_segment(lo, hi, &self.base).into_iter();
+            match hi.checked_add(1) {
+                Some(next) => self.next = next,
+                None => self.done = true,
+            }
+        }
+    }
+}
+
+// Smallest prime > n, or None past the largest u64 prime. Candidates are
+// sieved in small windows by the primes below 1000 and the survivors
+// confirmed with Miller-Rabin, so it doesn't need primes up to sqrt(n).
+pub fn next_prime_u64(n: u64) -> Option<u64> {
+    thread_local! {
+        static SMALL: Vec<u32> = Sieve::new(1000).primes().map(|p| p as u32).collect();
+    }
+    let mut lo = n.checked_add(1)?;
+    SMALL.with(|small| loop {
+        let hi = lo.saturating_add(1023);
+        for p in sieve_segment(lo, hi, small) {
+            if p < 1000 * 1000 || crate::prime::is_prime_u64(p) {
+                return Some(p);
+            }
+        }
+        lo = hi.checked_add(1)?;
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prime::is_prime_u64;
+
+    #[test]
+    fn sieve_test1() {
+        let s = Sieve::new(100);
+        let primes: Vec<u64> = s.primes().collect();
+        assert_eq!(primes, vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97]);
+        assert!(s.is_prime(97));
+        assert!(!s.is_prime(91));
+        assert!(!s.is_prime(0));
+        assert!(!s.is_prime(1));
+        assert_eq!(Sieve::new(0).count(), 0);
+        assert_eq!(Sieve::new(2).count(), 1);
+        assert_eq!(Sieve::new(4).count(), 2);
+        assert_eq!(Sieve::new(29).count(), 10);
+        assert_eq!(Sieve::new(31).count(), 11);
+    }
+
+    #[test]
+    fn sieve_count_test1() {
+        assert_eq!(Sieve::new(1_000_000).count(), 78498);
+        assert_eq!(Sieve::new(10_000_000).count(), 664579);
+    }
+
+    #[test]
+    fn sieve_matches_is_prime_test1() {
+        let s = Sieve::new(100_000);
+        for n in 0..=100_000 {
+            assert_eq!(s.is_prime(n), is_prime_u64(n), "{}", n);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn sieve_limit_test1() {
+        Sieve::new(100).is_prime(101);
+    }
+
+    #[test]
+    fn range_test1() {
+        assert_eq!(primes_in_range(0, 20), vec![2, 3, 5, 7, 11, 13, 17, 19]);
+        assert_eq!(primes_in_range(90, 100), vec![97]);
+        assert_eq!(primes_in_range(24, 28), vec![]);
+        assert_eq!(primes_in_range(1_000_000_000, 1_000_000_100), vec![1000000007, 1000000009, 1000000021, 1000000033, 1000000087, 1000000093, 1000000097]);
+        assert_eq!(primes_in_range(1_000_000_000_000, 1_000_000_000_100), vec![1000000000039, 1000000000061, 1000000000063, 1000000000091]);
+        let r = primes_in_range(999_000, 1_001_000);
+        assert!(r.iter().all(|&p| is_prime_u64(p)));
+        assert_eq!(r.len(), (999_000..=1_001_000).filter(|&n| is_prime_u64(n)).count());
+    }
+
+    #[test]
+    fn primes_iter_test1() {
+        assert_eq!(Primes::new().take(10).collect::<Vec<_>>(), vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+        assert_eq!(Primes::new().nth(9999), Some(104729));
+        // Through many segments, so the base grows a bit at a time.
+        assert_eq!(Primes::new().take_while(|&p| p <= 10_000_000).count(), 664579);
+    }
+
+    #[test]
+    fn primes_iter_base_test1() {
+        let mut it = Primes::starting_at(1_000_000_000_000);
+        assert_eq!(it.next(), Some(1000000000039));
+        let root = (1_000_000_000_000 + SEGMENT - 1).isqrt();
+        assert_eq!(it.base_limit, root);
+        assert_eq!(it.base.len(), 78498);
+    }
+
+    #[test]
+    fn next_prime_u64_test1() {
+        assert_eq!(next_prime_u64(0), Some(2));
+        assert_eq!(next_prime_u64(2), Some(3));
+        assert_eq!(next_prime_u64(997), Some(1009));
+        assert_eq!(next_prime_u64(1_000_000_000_000), Some(1_000_000_000_039));
+        assert_eq!(next_prime_u64(18446744073709551557), None);
+        for n in 0..5000 {
+            let p = next_prime_u64(n).unwrap();
+            assert!(is_prime_u64(p) && (n + 1..p).all(|m| !is_prime_u64(m)));
+        }
+    }
+}